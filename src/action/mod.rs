@@ -1,14 +1,26 @@
+use bear_lib_terminal::Color;
+use crate::combat;
+use combat::projectile::Projectile;
 use crate::command;
 use command::CompassDirection;
+use crate::component;
+use component::position::Position;
+use component::status::Kind as StatusKind;
 use crate::effect;
 use effect::Effect;
 use crate::game;
 use game::Game;
 use crate::species;
-use species::Factory as SpeciesFactory;
+use species::{Factory as SpeciesFactory, Species};
 
 const TIME_PER_TURN: i32 = 120;
 
+/// The number of turns for which mushroom spores confuse.
+const SPORE_DURATION: i32 = 5;
+
+/// The refreshment in a draught of water.
+const DRAUGHT_REFRESHMENT: i32 = 3;
+
 /// Actions are processes that modify the game world.
 #[derive(Clone, Copy, Debug)]
 pub enum Action {
@@ -16,10 +28,46 @@ pub enum Action {
     Walk(CompassDirection),
     /// Attack (melee) something in the specified direction.
     MeleeAttack(CompassDirection),
+    /// Shoot at the specified position.
+    RangedAttack(Position),
+    /// Throw something at the specified position.
+    Throw(Position),
     /// Just wait, wasting a turn.
     Wait,
     /// Stall -- don't waste turn, but don't do anything.
     Stall,
+    /// Start or stop sneaking.
+    ToggleSneak,
+    /// Eat a corpse at the entity's position.
+    Eat,
+    /// Graze on moss at the entity's position.
+    Graze,
+    /// Drink from the water at the entity's position.
+    Drink,
+    /// Fall asleep until rested.
+    Sleep,
+    /// Pick up an item at the entity's position.
+    PickUp,
+    /// Drop the specified carried item.
+    Drop(usize),
+    /// List the items carried.
+    ListInventory,
+    /// Wield or wear the specified carried item.
+    Equip(usize),
+    /// Stop wielding or wearing the specified item.
+    Unequip(usize),
+    /// Use up the specified carried item.
+    Consume(usize),
+    /// Open (or search) the specified container.
+    Open(usize),
+    /// Take the specified item from the specified container.
+    TakeFrom(usize, usize),
+    /// Put the specified carried item into the specified container.
+    PutInto(usize, usize),
+    /// Call on the entity's pack for help against the specified threat.
+    CallForHelp(usize),
+    /// Mushroom: Release spores onto whatever is touching it.
+    ReleaseSpores,
     /// Moss: Bloom.
     MossBloom,
     /// Moss: Seed.
@@ -32,14 +80,39 @@ pub enum Action {
 impl Action {
 
     /// Get the cost of performing this action.
-    pub fn get_cost(self, _id: usize, _game: &Game) -> i32 {
+    pub fn get_cost(self, id: usize, game: &Game) -> i32 {
         trace!("Entering Action::get_cost().");
         use Action::*;
         match self {
-            Walk(_) => TIME_PER_TURN,
+            Walk(_) => {
+                let is_sneaking = game.entities[id].perception.is_some_and(|perception| perception.is_sneaking);
+                if is_sneaking {
+                    TIME_PER_TURN * 3 / 2
+                } else {
+                    TIME_PER_TURN
+                }
+            },
             MeleeAttack(_) => TIME_PER_TURN,
+            RangedAttack(_) => TIME_PER_TURN,
+            Throw(_) => TIME_PER_TURN,
             Wait => TIME_PER_TURN,
             Stall => 0,
+            ToggleSneak => 0,
+            Eat => TIME_PER_TURN * 2,
+            Graze => TIME_PER_TURN * 2,
+            Drink => TIME_PER_TURN,
+            Sleep => TIME_PER_TURN,
+            PickUp => TIME_PER_TURN,
+            Drop(_) => TIME_PER_TURN,
+            ListInventory => 0,
+            Equip(_) => TIME_PER_TURN,
+            Unequip(_) => TIME_PER_TURN,
+            Consume(_) => TIME_PER_TURN,
+            Open(_) => TIME_PER_TURN,
+            TakeFrom(_, _) => TIME_PER_TURN / 2,
+            PutInto(_, _) => TIME_PER_TURN / 2,
+            CallForHelp(_) => TIME_PER_TURN / 2,
+            ReleaseSpores => TIME_PER_TURN,
             MossBloom => TIME_PER_TURN * 14,
             MossSeed(_) => TIME_PER_TURN * 20,
             MossDie => 1,
@@ -62,21 +135,34 @@ impl Action {
             },
             MeleeAttack(compass_direction) => {
                 let entity = &game.entities[id];
-                if let Some(entity_position) = &entity.position {
+                if let Some(entity_position) = entity.position {
                     let target_position = entity_position.to_direction(*compass_direction);
                     debug!("Entity {} elected to attack ({:?}).", entity.name, compass_direction);
-                    if let Some(target_entity) = &game.get_entities(target_position.x, target_position.y)
+                    let target_id = game.get_entities(target_position.x, target_position.y)
                         .iter()
-                        .filter(|x| x.body.is_some()).nth(0) {
-                            println!("Entity {} ({}, {}) attacks target entity {} ({}, {})!", entity.name, entity_position.x, entity_position.y, target_entity.name, target_position.x, target_position.y);
-                            return Some(Effect::DamageEntityBody(target_entity.id, 7));
+                        .filter(|x| x.body.is_some())
+                        .map(|x| x.id)
+                        .nth(0);
+                    if let Some(target_id) = target_id {
+                        return combat::attack(id, target_id, game);
                     }
                 }
                 None
             },
+            RangedAttack(position) => {
+                debug!("Entity {} elected to shoot at ({}, {}).", game.entities[id].name, position.x, position.y);
+                combat::fire(id, *position, Projectile::Missile, game)
+            },
+            Throw(position) => {
+                debug!("Entity {} elected to throw at ({}, {}).", game.entities[id].name, position.x, position.y);
+                combat::fire(id, *position, Projectile::Thrown, game)
+            },
             Wait => {
                 let entity = &game.entities[id];
                 debug!("Entity {} elected to wait ({}).", entity.name, entity.actor.unwrap().time);
+                if entity.body.is_some() && !game.can_see_hostile(id) {
+                    return Some(Effect::Rest);
+                }
                 None
             },
             Stall => {
@@ -84,6 +170,113 @@ impl Action {
                 debug!("Entity {} elected to stall for time ({}).", entity.name, entity.actor.unwrap().time);
                 None
             },
+            ToggleSneak => {
+                let entity = &game.entities[id];
+                if let Some(perception) = entity.perception {
+                    debug!("Entity {} elected to toggle sneaking ({}).", entity.name, !perception.is_sneaking);
+                    return Some(Effect::SetSneaking(!perception.is_sneaking));
+                }
+                None
+            },
+            Eat => {
+                let position = game.entities[id].position?;
+                let corpse_id = game.get_entities(position.x, position.y)
+                    .iter()
+                    .filter(|entity| entity.corpse.as_ref().is_some_and(|corpse| corpse.is_edible()))
+                    .map(|entity| entity.id)
+                    .nth(0)?;
+                debug!("Entity {} elected to eat entity {}.", game.entities[id].name, corpse_id);
+                Some(Effect::EatCorpse(corpse_id))
+            },
+            Graze => {
+                let position = game.entities[id].position?;
+                let moss_id = game.get_entities(position.x, position.y)
+                    .iter()
+                    .filter(|entity| entity.species == Some(Species::Moss))
+                    .map(|entity| entity.id)
+                    .nth(0)?;
+                debug!("Entity {} elected to graze on entity {}.", game.entities[id].name, moss_id);
+                Some(Effect::EatMoss(moss_id))
+            },
+            Drink => {
+                debug!("Entity {} elected to drink.", game.entities[id].name);
+                Some(Effect::Quench(DRAUGHT_REFRESHMENT))
+            },
+            Sleep => {
+                let duration = game.entities[id].needs.as_ref()?.get_sleep_duration();
+                debug!("Entity {} elected to sleep for {} turns.", game.entities[id].name, duration);
+                Some(Effect::InflictStatus(id, StatusKind::Asleep, duration))
+            },
+            PickUp => {
+                let position = game.entities[id].position?;
+                let mut item_ids = game.get_items(position.x, position.y)
+                    .iter()
+                    .filter(|item| game.can_carry(id, item.id))
+                    .map(|item| item.id)
+                    .collect::<Vec<usize>>();
+                item_ids.sort_unstable();
+                let item_id = *item_ids.first()?;
+                debug!("Entity {} elected to pick up entity {}.", game.entities[id].name, item_id);
+                Some(Effect::PickUpItem(item_id))
+            },
+            Drop(item_id) => {
+                debug!("Entity {} elected to drop entity {}.", game.entities[id].name, item_id);
+                Some(Effect::DropItem(*item_id))
+            },
+            ListInventory => {
+                let inventory = game.entities[id].inventory.clone()?;
+                let text = if inventory.items.is_empty() {
+                    "You are carrying nothing.".to_string()
+                } else {
+                    let names = inventory.items
+                        .iter()
+                        .map(|&item_id| game.get_item_name(item_id))
+                        .collect::<Vec<String>>();
+                    format!("You are carrying: {} ({}/{}).", names.join(", "), game.get_carried_weight(id), inventory.max_weight)
+                };
+                if id == game.player_id {
+                    game.add_message(text, Color::from_rgb(192, 192, 192));
+                }
+                None
+            },
+            Equip(item_id) => {
+                debug!("Entity {} elected to equip entity {}.", game.entities[id].name, item_id);
+                Some(Effect::EquipItem(*item_id))
+            },
+            Unequip(item_id) => {
+                debug!("Entity {} elected to unequip entity {}.", game.entities[id].name, item_id);
+                Some(Effect::UnequipItem(*item_id))
+            },
+            Consume(item_id) => {
+                debug!("Entity {} elected to consume entity {}.", game.entities[id].name, item_id);
+                Some(Effect::ConsumeItem(*item_id))
+            },
+            Open(container_id) => {
+                debug!("Entity {} elected to open entity {}.", game.entities[id].name, container_id);
+                Some(Effect::OpenContainer(*container_id))
+            },
+            TakeFrom(container_id, item_id) => {
+                debug!("Entity {} elected to take entity {} from entity {}.", game.entities[id].name, item_id, container_id);
+                Some(Effect::TakeItem(*container_id, *item_id))
+            },
+            PutInto(container_id, item_id) => {
+                debug!("Entity {} elected to put entity {} into entity {}.", game.entities[id].name, item_id, container_id);
+                Some(Effect::StowItem(*container_id, *item_id))
+            },
+            CallForHelp(threat_id) => {
+                debug!("Entity {} called for help against entity {}.", game.entities[id].name, threat_id);
+                Some(Effect::CallForHelp(*threat_id))
+            },
+            ReleaseSpores => {
+                let position = game.entities[id].position?;
+                let target_id = game.get_entities(position.x, position.y)
+                    .iter()
+                    .filter(|entity| entity.id != id && entity.body.is_some() && entity.blocks_movement && entity.species != game.entities[id].species)
+                    .map(|entity| entity.id)
+                    .nth(0)?;
+                debug!("Entity {} released spores onto entity {}.", game.entities[id].name, target_id);
+                Some(Effect::InflictStatus(target_id, StatusKind::Confused, SPORE_DURATION))
+            },
             MossBloom => {
                 let entity = &game.entities[id];
                 if let Some(position) = &entity.position {
@@ -0,0 +1,85 @@
+use std::cmp;
+use crate::agent;
+use agent::blackboard::Blackboard;
+use crate::combat;
+use crate::component;
+use component::needs::{Kind as NeedKind, Level as NeedLevel};
+use component::status::Kind as StatusKind;
+use crate::game;
+use game::Game;
+use crate::species;
+use species::Species;
+
+/// A question a behavior tree asks about the world or its own blackboard.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Condition {
+    /// The agent has noted a target.
+    HasTarget,
+    /// The target is next to the agent.
+    IsAdjacentToTarget,
+    /// The target is within range of the agent's ranged attack, with nothing in the way.
+    HasClearShotAtTarget,
+    /// Something else is standing on the agent.
+    IsTouched,
+    /// The agent's morale has broken.
+    IsFleeing,
+    /// The agent can see something it would fight, or that would fight it.
+    CanSeeEnemy,
+    /// The agent feels the need at least this badly.
+    NeedIs(NeedKind, NeedLevel),
+    /// The number of moss around the agent is one of these.
+    MossAroundIs(Vec<usize>),
+}
+
+/// A question a behavior tree asks about the world or its own blackboard.
+impl Condition {
+
+    /// Indicates whether the condition holds for the agent.
+    pub fn evaluate(&self, id: usize, game: &Game, blackboard: &Blackboard) -> bool {
+        use Condition::*;
+        match self {
+            HasTarget => blackboard.target_id.is_some(),
+            IsAdjacentToTarget => {
+                match (game.entities[id].position, blackboard.target_position) {
+                    (Some(position), Some(target_position)) => position.distance_to(&target_position) < 2.0,
+                    _ => false,
+                }
+            },
+            HasClearShotAtTarget => {
+                let entity = &game.entities[id];
+                let (position, target_position) = match (entity.position, blackboard.target_position) {
+                    (Some(position), Some(target_position)) => (position, target_position),
+                    _ => return false,
+                };
+                let range = entity.get_stats().map_or(0, |stats| stats.range);
+                let distance = cmp::max((position.x - target_position.x).abs(), (position.y - target_position.y).abs());
+                distance <= range && combat::has_line_of_fire(game, id, &target_position)
+            },
+            IsTouched => {
+                let position = match game.entities[id].position {
+                    Some(position) => position,
+                    None => return false,
+                };
+                game.get_entities(position.x, position.y)
+                    .iter()
+                    .any(|entity| entity.id != id && entity.body.is_some() && entity.blocks_movement && !entity.has_status(StatusKind::Confused))
+            },
+            IsFleeing => game.entities[id].morale.is_some_and(|morale| morale.is_fleeing),
+            CanSeeEnemy => !game.get_visible_enemies(id).is_empty(),
+            NeedIs(kind, level) => game.entities[id].needs.as_ref().is_some_and(|needs| needs.get_level(*kind) >= *level),
+            MossAroundIs(counts) => {
+                let position = match game.entities[id].position {
+                    Some(position) => position,
+                    None => return false,
+                };
+                let count = game.map
+                    .get_entities_around(position.x as usize, position.y as usize)
+                    .iter()
+                    .filter(|&&other_id| game.entities[other_id].species == Some(Species::Moss))
+                    .count();
+                counts.contains(&count)
+            },
+        }
+    }
+
+}
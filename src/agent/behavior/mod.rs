@@ -0,0 +1,206 @@
+use std::io::Read;
+use std::fs::File;
+use std::error::Error;
+use rand::Rng;
+use crate::agent;
+use agent::Algorithm;
+use agent::blackboard::Blackboard;
+use crate::command;
+use command::Command;
+use crate::game;
+use game::Game;
+use crate::seed;
+use seed::RngType;
+
+/// Conditions, the questions a tree asks.
+pub mod condition;
+use condition::Condition;
+
+/// Tasks, the things a tree does.
+pub mod task;
+use task::Task;
+
+/// The filename.
+pub const BEHAVIOR_FILENAME: &str = "resources/azymus/agent/behavior.json";
+
+/// The result of running a node.
+#[derive(Clone, Copy, Debug)]
+pub enum Status {
+    /// The node succeeded without issuing a command.
+    Success,
+    /// The node failed.
+    Failure,
+    /// The node issued a command, which ends the agent's turn.
+    Running(Command),
+}
+
+/// The result of running a node.
+impl Status {
+
+    /// A task that issues the command if there is one, and fails otherwise.
+    pub fn from_command(command: Option<Command>) -> Status {
+        match command {
+            Some(command) => Status::Running(command),
+            None => Status::Failure,
+        }
+    }
+
+}
+
+/// A node in a behavior tree.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Node {
+    /// Run each child in turn until one fails or issues a command.
+    Sequence(Vec<Node>),
+    /// Run each child in turn until one succeeds or issues a command.
+    Selector(Vec<Node>),
+    /// Swap the success or failure of the child.
+    Invert(Box<Node>),
+    /// Succeed even if the child fails.
+    Succeed(Box<Node>),
+    /// Run the child only some percentage of the time; fail otherwise.
+    Chance(u32, Box<Node>),
+    /// Succeed if the condition holds; fail otherwise.
+    Condition(Condition),
+    /// Perform a task.
+    Task(Task),
+}
+
+/// A node in a behavior tree.
+impl Node {
+
+    /// Run this node for the agent.
+    pub fn run(&self, id: usize, game: &Game, blackboard: &mut Blackboard, rng: &mut RngType) -> Status {
+        use Node::*;
+        match self {
+            Sequence(children) => {
+                for child in children {
+                    match child.run(id, game, blackboard, rng) {
+                        Status::Success => continue,
+                        status => return status,
+                    }
+                }
+                Status::Success
+            },
+            Selector(children) => {
+                for child in children {
+                    match child.run(id, game, blackboard, rng) {
+                        Status::Failure => continue,
+                        status => return status,
+                    }
+                }
+                Status::Failure
+            },
+            Invert(child) => match child.run(id, game, blackboard, rng) {
+                Status::Success => Status::Failure,
+                Status::Failure => Status::Success,
+                status => status,
+            },
+            Succeed(child) => match child.run(id, game, blackboard, rng) {
+                Status::Failure => Status::Success,
+                status => status,
+            },
+            Chance(percent, child) => {
+                if rng.gen_range(0, 100) >= *percent {
+                    return Status::Failure;
+                }
+                child.run(id, game, blackboard, rng)
+            },
+            Condition(condition) => {
+                if condition.evaluate(id, game, blackboard) {
+                    Status::Success
+                } else {
+                    Status::Failure
+                }
+            },
+            Task(task) => task.perform(id, game, blackboard, rng),
+        }
+    }
+
+    /// Indicates whether this node or any beneath it recalls what the agent has seen.
+    pub fn uses_memory(&self) -> bool {
+        use Node::*;
+        match self {
+            Sequence(children) | Selector(children) => children.iter().any(|child| child.uses_memory()),
+            Invert(child) | Succeed(child) | Chance(_, child) => child.uses_memory(),
+            Condition(_) => false,
+            Task(task) => task.uses_memory(),
+        }
+    }
+
+}
+
+/// A behavior tree, attached to agents by algorithm.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Tree {
+    /// The algorithm this tree implements.
+    pub algorithm: Algorithm,
+    /// The root node.
+    pub root: Node,
+}
+
+/// All of the behavior trees.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BehaviorTrees {
+    /// The trees.
+    pub trees: Vec<Tree>,
+}
+
+/// All of the behavior trees.
+impl BehaviorTrees {
+
+    /// The tree implementing the specified algorithm, if any.
+    pub fn get(&self, algorithm: Algorithm) -> Option<&Node> {
+        self.trees
+            .iter()
+            .find(|tree| tree.algorithm == algorithm)
+            .map(|tree| &tree.root)
+    }
+
+}
+
+/// Load the behavior trees.
+pub fn load() -> Result<BehaviorTrees, Box<dyn Error>> {
+    let mut data = String::new();
+    let mut file = File::open(BEHAVIOR_FILENAME)?;
+    file.read_to_string(&mut data)?;
+    let result = serde_json::from_str::<BehaviorTrees>(&data)?;
+    Ok(result)
+}
+
+/// Load the behavior trees, or leave agents idle if they can't be loaded.
+pub fn get_behavior_trees() -> BehaviorTrees {
+    match load() {
+        Ok(behavior_trees) => behavior_trees,
+        Err(error) => {
+            warn!("Could not load behavior trees: {}", error);
+            BehaviorTrees::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// The shipped behavior trees parse, and cover every algorithm species use.
+    #[test]
+    fn parse() {
+        let data = include_str!("../../../resources/azymus/agent/behavior.json");
+        let behavior_trees = serde_json::from_str::<BehaviorTrees>(data).unwrap();
+        let algorithms = [
+            Algorithm::ApproachPlayer,
+            Algorithm::ApproachAndFightPlayer,
+            Algorithm::ApproachAndShootPlayer,
+            Algorithm::BeChicken,
+            Algorithm::BeMushroom,
+            Algorithm::BeMoss,
+            Algorithm::BeMossSeed,
+        ];
+        for &algorithm in algorithms.iter() {
+            assert!(behavior_trees.get(algorithm).is_some(), "No tree for {:?}.", algorithm);
+        }
+    }
+
+}
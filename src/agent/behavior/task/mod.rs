@@ -0,0 +1,232 @@
+use rand::Rng;
+use crate::agent;
+use agent::blackboard::Blackboard;
+use agent::behavior::Status;
+use crate::command;
+use command::{Command, CompassDirection};
+use crate::component;
+use component::position::Position;
+use crate::game;
+use game::Game;
+use crate::pack;
+use crate::pathfinding;
+use pathfinding::dijkstra::Kind as DijkstraMapKind;
+use crate::seed;
+use seed::RngType;
+use crate::species;
+use species::Species;
+
+/// Something a behavior tree can do, usually by issuing a command.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Task {
+    /// Note the player as the target if the agent can see them; fails otherwise.
+    ///
+    /// A target that is no longer in sight is still remembered.
+    TargetPlayer,
+    /// Recall where the target was last seen; fails if it has been forgotten.
+    RecallTarget,
+    /// Forget about the target.
+    ForgetTarget,
+    /// Go to where the target was last seen, and start searching on arrival.
+    Investigate,
+    /// Wander in search of a lost target for a while.
+    Search,
+    /// Move towards the target.
+    ApproachTarget,
+    /// Move to a free cell around the target that no packmate is closer to.
+    FlankTarget,
+    /// Call on the pack for help against the target, once per target.
+    CallForHelp,
+    /// Take up a place in formation around the pack leader.
+    FollowLeader,
+    /// Attack the target in melee.
+    AttackTarget,
+    /// Attack the target from range.
+    ShootTarget,
+    /// Run away from everything in sight that would attack the agent.
+    FleeEnemies,
+    /// Move towards the nearest ally in sight, unless already close.
+    Regroup,
+    /// Stay put, resting if nothing is around.
+    Rest,
+    /// Eat or move towards a nearby edible corpse.
+    Scavenge,
+    /// Graze on moss, or make for the nearest.
+    Graze,
+    /// Drink from a puddle, or make for the nearest.
+    Drink,
+    /// Fall asleep until rested.
+    Sleep,
+    /// Walk in the specified direction.
+    Walk(CompassDirection),
+    /// Walk in a random direction.
+    Wander,
+    /// Move downhill on one of the shared Dijkstra maps.
+    FollowDijkstraMap(DijkstraMapKind),
+    /// Release spores onto whatever is standing here.
+    ReleaseSpores,
+    /// Drop a moss seed in an open space nearby.
+    SeedMoss,
+    /// Bloom into moss.
+    MossBloom,
+    /// Die off.
+    MossDie,
+}
+
+/// Something a behavior tree can do, usually by issuing a command.
+impl Task {
+
+    /// Perform the task for the agent.
+    pub fn perform(&self, id: usize, game: &Game, blackboard: &mut Blackboard, rng: &mut RngType) -> Status {
+        use Task::*;
+        match self {
+            TargetPlayer => {
+                let player_id = game.player_id;
+                if !game.can_see_entity(id, player_id) {
+                    return Status::Failure;
+                }
+                blackboard.set_target(player_id, game.entities[player_id].position);
+                Status::Success
+            },
+            RecallTarget => {
+                let sighting = blackboard.target_id.and_then(|target_id| blackboard.memory.recall(target_id));
+                match sighting {
+                    Some(sighting) => {
+                        blackboard.target_position = Some(sighting.position);
+                        Status::Success
+                    },
+                    None => {
+                        blackboard.clear_target();
+                        Status::Failure
+                    },
+                }
+            },
+            ForgetTarget => {
+                blackboard.clear_target();
+                Status::Success
+            },
+            Investigate => {
+                let (position, target_position) = match (game.entities[id].position, blackboard.target_position) {
+                    (Some(position), Some(target_position)) => (position, target_position),
+                    _ => return Status::Failure,
+                };
+                let command = if position.distance_to(&target_position) < 1.0 {
+                    None
+                } else {
+                    agent::command_to_move_towards(id, &target_position, game)
+                };
+                if command.is_none() {
+                    debug!("Entity {} lost the trail; searching.", game.entities[id].name);
+                    if let Some(target_id) = blackboard.target_id {
+                        blackboard.memory.forget(target_id);
+                    }
+                    blackboard.clear_target();
+                    blackboard.search_turns = agent::SEARCH_TURNS;
+                }
+                Status::from_command(command)
+            },
+            Search => {
+                if blackboard.search_turns <= 0 {
+                    return Status::Failure;
+                }
+                blackboard.search_turns -= 1;
+                Status::from_command(agent::command_to_search(id, game, rng))
+            },
+            ApproachTarget => {
+                let command = blackboard.target_position
+                    .and_then(|position| agent::command_to_move_towards(id, &position, game));
+                Status::from_command(command)
+            },
+            FlankTarget => {
+                let command = blackboard.target_position
+                    .and_then(|position| agent::command_to_flank(id, &position, game));
+                Status::from_command(command)
+            },
+            CallForHelp => {
+                let target_id = match blackboard.target_id {
+                    Some(target_id) if !blackboard.has_called => target_id,
+                    _ => return Status::Failure,
+                };
+                if pack::get_packmates(id, game).is_empty() {
+                    return Status::Failure;
+                }
+                blackboard.has_called = true;
+                Status::Running(Command::CallForHelp(target_id))
+            },
+            FollowLeader => Status::from_command(agent::command_to_follow_leader(id, game)),
+            AttackTarget => {
+                let command = blackboard.target_position
+                    .and_then(|position| agent::command_to_attack(id, &position, game));
+                Status::from_command(command)
+            },
+            ShootTarget => {
+                let command = blackboard.target_position
+                    .map(Command::RangedAttack);
+                Status::from_command(command)
+            },
+            FleeEnemies => {
+                let threat_ids = game.get_visible_threats(id);
+                Status::from_command(agent::command_to_flee(id, &threat_ids, game))
+            },
+            Regroup => Status::from_command(agent::command_to_regroup(id, game)),
+            Rest => Status::Running(Command::Wait),
+            Scavenge => Status::from_command(agent::command_to_scavenge(id, game)),
+            Graze => Status::from_command(agent::command_to_graze(id, game)),
+            Drink => Status::from_command(agent::command_to_drink(id, game)),
+            Sleep => Status::Running(Command::Sleep),
+            Walk(compass_direction) => Status::Running(Command::Walk(*compass_direction)),
+            Wander => Status::Running(Command::Walk(rng.gen::<CompassDirection>())),
+            FollowDijkstraMap(kind) => Status::from_command(agent::command_to_follow(id, *kind, game)),
+            ReleaseSpores => Status::Running(Command::ReleaseSpores),
+            SeedMoss => Status::from_command(command_to_seed_moss(id, game, rng)),
+            MossBloom => Status::Running(Command::MossBloom),
+            MossDie => Status::Running(Command::MossDie),
+        }
+    }
+
+    /// Indicates whether the task recalls what the agent has seen.
+    pub fn uses_memory(&self) -> bool {
+        matches!(self, Task::RecallTarget)
+    }
+
+}
+
+/// Drop a moss seed in a random open space around the entity, if there is one.
+fn command_to_seed_moss(id: usize, game: &Game, rng: &mut RngType) -> Option<Command> {
+    let entity = &game.entities[id];
+    let position = entity.position?;
+    let map = &game.map;
+    debug!("Entity {} ({}, {}) is following the moss-seed rule.", entity.name, position.x, position.y);
+    let mut seed_positions: Vec<Position> = vec![];
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if dx == dy && dx == 0 {
+                continue;
+            }
+            let final_x = (position.x + dx) as usize;
+            let final_y = (position.y + dy) as usize;
+            if !map.is_in_bounds(final_x, final_y) {
+                continue;
+            }
+            let tile = map.get_tile(final_x, final_y);
+            if tile.blocks_movement || tile.blocks_light {
+                continue;
+            }
+            let is_overgrown = map.get_entities(final_x, final_y).is_some_and(|entities| {
+                entities
+                    .iter()
+                    .any(|&other_id| matches!(game.entities[other_id].species, Some(Species::MossSeed) | Some(Species::Moss)))
+            });
+            if !is_overgrown {
+                seed_positions.push(Position::new(position.w, final_x as i32, final_y as i32, position.z));
+            }
+        }
+    }
+    if seed_positions.is_empty() {
+        return None;
+    }
+    let index = rng.gen_range(0, seed_positions.len());
+    position
+        .direction_to(&seed_positions[index])
+        .map(Command::MossSeed)
+}
@@ -0,0 +1,61 @@
+use crate::agent;
+use agent::memory::Memory;
+use crate::component;
+use component::position::Position;
+use crate::game;
+use game::Game;
+
+/// The number of turns an agent remembers an enemy it can no longer see.
+const MEMORY_TURNS: usize = 50;
+
+/// An agent's private notes, shared between the nodes of its behavior tree.
+#[derive(Clone, Debug, Default)]
+pub struct Blackboard {
+    /// The entity the agent is currently concerned with, if any.
+    pub target_id: Option<usize>,
+    /// Where the target was last seen, if anywhere.
+    pub target_position: Option<Position>,
+    /// What the agent remembers of the enemies it has seen.
+    pub memory: Memory,
+    /// The number of turns left to search for a lost target.
+    pub search_turns: i32,
+    /// Indicates that the agent has already called its pack for help against the target.
+    pub has_called: bool,
+}
+
+/// An agent's private notes, shared between the nodes of its behavior tree.
+impl Blackboard {
+
+    /// Constructor.
+    pub fn new() -> Blackboard {
+        Blackboard::default()
+    }
+
+    /// Note the entity as the target, at its current position.
+    pub fn set_target(&mut self, target_id: usize, position: Option<Position>) {
+        if self.target_id != Some(target_id) {
+            self.has_called = false;
+        }
+        self.target_id = Some(target_id);
+        self.target_position = position;
+        self.search_turns = 0;
+    }
+
+    /// Forget about the target.
+    pub fn clear_target(&mut self) {
+        self.target_id = None;
+        self.target_position = None;
+        self.has_called = false;
+    }
+
+    /// Take note of every enemy the agent can see, and let old sightings fade.
+    pub fn observe(&mut self, id: usize, game: &Game) {
+        for enemy_id in game.get_visible_enemies(id) {
+            if let Some(position) = game.entities[enemy_id].position {
+                self.memory.remember(enemy_id, position, game.turns);
+            }
+        }
+        self.memory.forget_before(game.turns.saturating_sub(MEMORY_TURNS));
+    }
+
+}
@@ -0,0 +1,81 @@
+use crate::component;
+use component::position::Position;
+
+/// A record of where an entity was seen, and when.
+#[derive(Clone, Copy, Debug)]
+pub struct Sighting {
+    /// The entity seen.
+    pub entity_id: usize,
+    /// Where it was seen.
+    pub position: Position,
+    /// The turn on which it was seen.
+    pub turn: usize,
+}
+
+/// What an agent remembers of the entities it has seen.
+#[derive(Clone, Debug, Default)]
+pub struct Memory {
+    /// The most recent sighting of each entity.
+    pub sightings: Vec<Sighting>,
+}
+
+/// What an agent remembers of the entities it has seen.
+impl Memory {
+
+    /// Constructor.
+    pub fn new() -> Memory {
+        Memory::default()
+    }
+
+    /// Remember seeing an entity, replacing any older sighting of it.
+    pub fn remember(&mut self, entity_id: usize, position: Position, turn: usize) {
+        self.forget(entity_id);
+        self.sightings.push(Sighting {
+            entity_id,
+            position,
+            turn,
+        });
+    }
+
+    /// The most recent sighting of an entity, if any.
+    pub fn recall(&self, entity_id: usize) -> Option<Sighting> {
+        self.sightings
+            .iter()
+            .find(|sighting| sighting.entity_id == entity_id)
+            .copied()
+    }
+
+    /// Forget an entity entirely.
+    pub fn forget(&mut self, entity_id: usize) {
+        self.sightings.retain(|sighting| sighting.entity_id != entity_id);
+    }
+
+    /// Forget every sighting made before the specified turn.
+    pub fn forget_before(&mut self, turn: usize) {
+        self.sightings.retain(|sighting| sighting.turn >= turn);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Only the latest sighting of each entity is kept, until it grows stale.
+    #[test]
+    fn remember() {
+        let mut memory = Memory::new();
+        memory.remember(3, Position::new([0; 32], 1, 1, 0), 10);
+        memory.remember(3, Position::new([0; 32], 2, 2, 0), 12);
+        memory.remember(4, Position::new([0; 32], 5, 5, 0), 20);
+        assert_eq!(memory.sightings.len(), 2);
+        assert_eq!(memory.recall(3).map(|sighting| sighting.position.x), Some(2));
+        memory.forget_before(15);
+        assert!(memory.recall(3).is_none());
+        assert!(memory.recall(4).is_some());
+        memory.forget(4);
+        assert!(memory.sightings.is_empty());
+    }
+
+}
@@ -1,25 +1,91 @@
-use rand::*;
+use rand::Rng;
 use crate::command;
-use command::Command;
-use command::CompassDirection;
+use command::{Command, CompassDirection, COMPASS_DIRECTIONS};
 use crate::component;
 use component::position::Position;
-//use crate::entity;
-//use entity::Entity;
 use crate::game;
 use game::Game;
+use crate::pack;
+use crate::pathfinding;
+use pathfinding::dijkstra::{DijkstraMap, Kind as DijkstraMapKind};
+use crate::seed;
+use seed::RngType;
 use crate::species;
 use species::Species;
 
+/// How strongly fleeing agents prefer doubling back past a threat to being cornered.
+const FLEE_COEFFICIENT: f64 = 1.2;
+
+/// The distance within which a regrouping agent is close enough to its allies.
+const REGROUP_DISTANCE: f32 = 3.0;
+
+/// The number of turns an agent spends searching for a target it has lost.
+const SEARCH_TURNS: i32 = 12;
+
+/// The distance within which a follower is close enough to its place in formation.
+const FORMATION_DISTANCE: f32 = 1.5;
+
+/// Behavior trees, which decide what agents do.
+pub mod behavior;
+use behavior::Status;
+
+/// Blackboards, on which agents keep notes between turns.
+pub mod blackboard;
+use blackboard::Blackboard;
+
+/// Memories of the entities agents have seen.
+pub mod memory;
+
+/// Utility agents, which weigh their options rather than follow a script.
+pub mod utility;
+use utility::Profile as UtilityProfile;
+
 /// Something that can act autonomously.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Agent {
     /// The algorithm used by this agent.
     pub algorithm: Algorithm,
+    /// The agent's notes.
+    pub blackboard: Blackboard,
+}
+
+/// Something that can act autonomously.
+impl Agent {
+
+    /// Constructor.
+    pub fn new(algorithm: Algorithm) -> Agent {
+        Agent {
+            algorithm,
+            blackboard: Blackboard::new(),
+        }
+    }
+
+    /// Get the command that this agent would like to execute.
+    pub fn get_command(&mut self, time: i32, id: usize, game: &Game, rng: &mut RngType) -> Option<Command> {
+        trace!("Entering Agent::get_command().");
+        if time <= 0 {
+            return None;
+        }
+        if let Algorithm::Utility(profile) = self.algorithm {
+            self.blackboard.observe(id, game);
+            return utility::get_command(profile, time, id, game, &self.blackboard.memory);
+        }
+        let tree = game.behavior_trees.get(self.algorithm)?;
+        if tree.uses_memory() {
+            self.blackboard.observe(id, game);
+        }
+        match tree.run(id, game, &mut self.blackboard, rng) {
+            Status::Running(command) => Some(command),
+            _ => None,
+        }
+    }
+
 }
 
 /// Algorithms used to vend commands when given a context.
-#[derive(Clone, Copy, Debug)]
+///
+/// Most name a behavior tree, defined in data.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Algorithm {
     /// Just move South.
     JustMoveSouth,
@@ -27,6 +93,8 @@ pub enum Algorithm {
     ApproachPlayer,
     /// Approach and fight the player.
     ApproachAndFightPlayer,
+    /// Shoot the player when there's a clear shot; otherwise approach and fight.
+    ApproachAndShootPlayer,
     /// Be a chicken.
     BeChicken,
     /// Be a mushroom.
@@ -35,145 +103,10 @@ pub enum Algorithm {
     BeMoss,
     /// Just be a moss seed.
     BeMossSeed,
-}
-
-/// Algorithms used to vend commands when given a context.
-impl Algorithm {
-
-    /// Get the command that this agent would like to execute.
-    pub fn get_command(self, time: i32, id: usize, game: &Game) -> Option<Command> {
-        trace!("Entering Algorithm::get_command().");
-        use Algorithm::*;
-        if time <= 0 {
-            return None;
-        }
-        match self {
-            JustMoveSouth => {
-                Some(Command::Walk(CompassDirection::South))
-            },
-            ApproachPlayer => {
-                let player = &game.entities[game.player_id];
-                if let Some(player_position) = &player.position {
-                    return command_to_move_towards(id, player_position, game);
-                }
-                None
-            },
-            ApproachAndFightPlayer => {
-                let player = &game.entities[game.player_id];
-                if let Some(player_position) = &player.position {
-                    let entity = &game.entities[id];
-                    if let Some(entity_position) = &entity.position {
-                        if entity_position.distance_to(player_position) >= 2.0 {
-                            return command_to_move_towards(id, player_position, game)
-                        } else {
-                            return command_to_attack(id, player_position, game);
-                        }
-                    }
-                }
-                None
-            },
-            BeChicken => {
-                let mut rng = thread_rng();
-                if rng.gen::<bool>() {
-                    let direction = rng.gen::<CompassDirection>();
-                    Some(Command::Walk(direction))
-                } else {
-                    None
-                }
-            },
-            BeMushroom => {
-                None
-            },
-            BeMoss => {
-                let entity = &game.entities[id];
-                if let Some(position) = entity.position {
-                    let entities = &game.map
-                        .get_entities_around(position.x as usize, position.y as usize)
-                        .iter()
-                        .map(|&id| &game.entities[id])
-                        .cloned()
-                        .filter(|e| e.species.is_some() && e.species.unwrap() == Species::Moss)
-                        .collect::<Vec<_>>();
-                    let count = entities.len();
-                    match count {
-                        1 | 3 | 5 | 8 => {
-                            let map = &game.map;
-                            let mut seed_positions: Vec<Position> = vec![];
-                            if let Some(position) = &entity.position {
-                                debug!("Entity {} ({}, {}) is following the moss-seed rule.", entity.name, position.x, position.y);
-                                for dy in -1..=1 {
-                                    for dx in -1..=1 {
-                                        if dx == dy && dx == 0 {
-                                            continue;
-                                        }
-                                        let final_x = (position.x + dx) as usize;
-                                        let final_y = (position.y + dy) as usize;
-                                        if !map.is_in_bounds(final_x, final_y) {
-                                            continue;
-                                        }
-                                        let mut seed_here: bool = true;
-                                        let tile = map.get_tile(final_x, final_y);
-                                        if tile.blocks_movement || tile.blocks_light {
-                                            seed_here = false;
-                                        }
-                                        if let Some(entities) = map.get_entities(final_x, final_y) {
-                                            for id in entities {
-                                                let entity = &game.entities[id];
-                                                if let Some(species) = entity.species {
-                                                    if species == Species::MossSeed || species == Species::Moss {
-                                                        seed_here = false;
-                                                        break;
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        if seed_here {
-                                            seed_positions.push(Position {
-                                                w: position.w,
-                                                x: final_x as i32,
-                                                y: final_y as i32,
-                                                z: position.z,
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                            if seed_positions.len() == 0 {
-                                return None;
-                            }
-                            let mut rng = thread_rng();
-                            let index = rng.gen_range(0, seed_positions.len());
-                            if let Some(direction) = position.direction_to(&seed_positions[index]) {
-                                return Some(Command::MossSeed(direction));
-                            }
-                        },
-                        _ => return Some(Command::MossDie),
-                        //_ => {},
-                    }
-                }
-                None
-            },
-            BeMossSeed => {
-                let moss_seed = &game.entities[id];
-                if let Some(position) = moss_seed.position {
-                    let entities = &game.map
-                        .get_entities_around(position.x as usize, position.y as usize)
-                        .iter()
-                        .map(|&id| &game.entities[id])
-                        .cloned()
-                        .filter(|e| e.species.is_some() && e.species.unwrap() == Species::Moss)
-                        .collect::<Vec<_>>();
-                    let count = entities.len();
-                    match count {
-                        3 | 5 | 7 => return Some(Command::MossBloom),
-                        _ => return None,
-                    }
-                }
-                None
-            },
-        }
-    }
-
+    /// Move downhill on one of the shared Dijkstra maps.
+    FollowDijkstraMap(DijkstraMapKind),
+    /// Weigh the options with the specified temperament.
+    Utility(UtilityProfile),
 }
 
 fn get_direction_to(id: usize, position: &Position, game: &Game) -> Option<CompassDirection> {
@@ -185,6 +118,9 @@ fn get_direction_to(id: usize, position: &Position, game: &Game) -> Option<Compa
 }
 
 fn command_to_move_towards(id: usize, position: &Position, game: &Game) -> Option<Command> {
+    if let Some(compass_direction) = pathfinding::get_next_step(game, id, position) {
+        return Some(Command::Walk(compass_direction));
+    }
     if let Some(compass_direction) = get_direction_to(id, position, game) {
         return Some(Command::Walk(compass_direction));
     }
@@ -197,3 +133,134 @@ fn command_to_attack(id: usize, position: &Position, game: &Game) -> Option<Comm
     }
     None
 }
+
+fn command_to_descend(id: usize, dijkstra_map: &DijkstraMap, game: &Game) -> Option<Command> {
+    let position = game.entities[id].position?;
+    let (dx, dy) = dijkstra_map.get_downhill_offset(position.x, position.y, |x, y| {
+        pathfinding::is_passable(game, id, x, y)
+    })?;
+    let target = Position::new(position.w, position.x + dx, position.y + dy, position.z);
+    command_to_move_towards(id, &target, game)
+}
+
+fn command_to_follow(id: usize, kind: DijkstraMapKind, game: &Game) -> Option<Command> {
+    command_to_descend(id, game.dijkstra_maps.get(&kind)?, game)
+}
+
+/// Compute a map leading away from the specified threats, if any have a position.
+pub fn get_flee_map(threat_ids: &[usize], game: &Game) -> Option<DijkstraMap> {
+    let goals = threat_ids
+        .iter()
+        .filter_map(|&threat_id| game.entities[threat_id].position)
+        .map(|position| (position.x, position.y, 0))
+        .collect::<Vec<_>>();
+    if goals.is_empty() {
+        return None;
+    }
+    let get_cost = |x: i32, y: i32| pathfinding::get_terrain_cost(game, x, y);
+    let distance = DijkstraMap::compute(game.map.width, game.map.height, &goals, get_cost);
+    Some(distance.invert(FLEE_COEFFICIENT, get_cost))
+}
+
+fn command_to_flee(id: usize, threat_ids: &[usize], game: &Game) -> Option<Command> {
+    if let Some(flee_map) = game.flee_maps.get(threat_ids) {
+        return command_to_descend(id, flee_map, game);
+    }
+    command_to_descend(id, &get_flee_map(threat_ids, game)?, game)
+}
+
+fn command_to_regroup(id: usize, game: &Game) -> Option<Command> {
+    let position = game.entities[id].position?;
+    let (_, ally_position) = game.get_visible_allies(id)
+        .into_iter()
+        .filter_map(|ally_id| game.entities[ally_id].position)
+        .map(|ally_position| ((position.distance_to(&ally_position) * 100.0) as i32, ally_position))
+        .min_by_key(|&(distance, _)| distance)?;
+    if position.distance_to(&ally_position) < REGROUP_DISTANCE {
+        return None;
+    }
+    command_to_move_towards(id, &ally_position, game)
+}
+
+fn command_to_flank(id: usize, target_position: &Position, game: &Game) -> Option<Command> {
+    game.entities[id].group.as_ref()?;
+    let position = game.entities[id].position?;
+    let is_engaged = |other: &Position| (other.x - target_position.x).abs() <= 1 && (other.y - target_position.y).abs() <= 1;
+    let packmates = pack::get_packmates(id, game)
+        .into_iter()
+        .filter_map(|packmate_id| game.entities[packmate_id].position)
+        .filter(|packmate_position| !is_engaged(packmate_position))
+        .map(|packmate_position| (packmate_position.x, packmate_position.y))
+        .collect::<Vec<(i32, i32)>>();
+    let free_cells = COMPASS_DIRECTIONS
+        .iter()
+        .map(|&compass_direction| target_position.to_direction(compass_direction))
+        .filter(|cell| game.map.is_position_in_bounds(cell) && pathfinding::is_passable(game, id, cell.x, cell.y))
+        .map(|cell| (cell.x, cell.y))
+        .collect::<Vec<(i32, i32)>>();
+    let (x, y) = pack::choose_flank(&free_cells, (position.x, position.y), &packmates)?;
+    command_to_move_towards(id, &Position::new(position.w, x, y, position.z), game)
+}
+
+fn command_to_follow_leader(id: usize, game: &Game) -> Option<Command> {
+    let entity = &game.entities[id];
+    let group = entity.group?;
+    if group.is_leader() {
+        return None;
+    }
+    let position = entity.position?;
+    let leader_position = game.entities[group.leader_id].position?;
+    let (dx, dy) = group.get_formation_offset();
+    let place = Position::new(leader_position.w, leader_position.x + dx, leader_position.y + dy, leader_position.z);
+    if position.distance_to(&place) < FORMATION_DISTANCE {
+        return None;
+    }
+    command_to_move_towards(id, &place, game)
+}
+
+fn command_to_search(id: usize, game: &Game, rng: &mut RngType) -> Option<Command> {
+    let position = game.entities[id].position?;
+    let start = rng.gen_range(0, COMPASS_DIRECTIONS.len());
+    (0..COMPASS_DIRECTIONS.len())
+        .map(|offset| COMPASS_DIRECTIONS[(start + offset) % COMPASS_DIRECTIONS.len()])
+        .find(|&compass_direction| {
+            let next = position.to_direction(compass_direction);
+            game.map.is_position_in_bounds(&next) && pathfinding::is_passable(game, id, next.x, next.y)
+        })
+        .map(Command::Walk)
+}
+
+fn command_to_graze(id: usize, game: &Game) -> Option<Command> {
+    let position = game.entities[id].position?;
+    let is_on_moss = game.get_entities(position.x, position.y)
+        .iter()
+        .any(|entity| entity.species == Some(Species::Moss));
+    if is_on_moss {
+        return Some(Command::Graze);
+    }
+    command_to_follow(id, DijkstraMapKind::DistanceToFood, game)
+}
+
+fn command_to_drink(id: usize, game: &Game) -> Option<Command> {
+    let position = game.entities[id].position?;
+    if game.map.get_tile_at_position(&position).is_drinkable {
+        return Some(Command::Drink);
+    }
+    command_to_follow(id, DijkstraMapKind::DistanceToWater, game)
+}
+
+fn command_to_scavenge(id: usize, game: &Game) -> Option<Command> {
+    let position = game.entities[id].position?;
+    let is_edible = |position: &Position| {
+        game.map.is_position_in_bounds(position) && game.get_entities(position.x, position.y)
+            .iter()
+            .any(|entity| entity.corpse.as_ref().is_some_and(|corpse| corpse.is_edible()))
+    };
+    if is_edible(&position) {
+        return Some(Command::Eat);
+    }
+    COMPASS_DIRECTIONS
+        .iter()
+        .find(|&&compass_direction| is_edible(&position.to_direction(compass_direction)))
+        .map(|&compass_direction| Command::Walk(compass_direction))
+}
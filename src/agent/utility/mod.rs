@@ -0,0 +1,435 @@
+use std::cmp;
+use crate::agent;
+use agent::memory::Memory;
+use crate::combat;
+use crate::command;
+use command::{Command, COMPASS_DIRECTIONS};
+use crate::component;
+use component::needs::Kind as NeedKind;
+use component::position::Position;
+use crate::game;
+use game::Game;
+use crate::math;
+use math::noise;
+use crate::pathfinding;
+use pathfinding::dijkstra::Kind as DijkstraMapKind;
+
+/// The most that seeded noise can add to a score, so that close calls vary.
+const NOISE: f32 = 0.1;
+
+/// The distance at which a threat stops mattering.
+const THREAT_RANGE: f32 = 12.0;
+
+/// Something an agent can measure about its situation, as a value from 0 to 1.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Input {
+    /// How far away the nearest visible threat is; 1 if there is none in range.
+    DistanceToThreat,
+    /// How much of its health the agent has left.
+    HitPointFraction,
+    /// How badly the agent needs to eat.
+    Hunger,
+    /// How badly the agent needs to drink.
+    Thirst,
+    /// How badly the agent needs to sleep.
+    Fatigue,
+    /// How brightly lit the agent's position is.
+    LightLevel,
+    /// How much of its will to fight the agent has left.
+    Morale,
+}
+
+/// A response curve, mapping an input to a score from 0 to 1.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Curve {
+    /// The input itself.
+    Linear,
+    /// The complement of the input.
+    Inverse,
+    /// The square of the input, which stays low until the input is high.
+    Square,
+    /// The square of the complement, which stays low until the input is low.
+    InverseSquare,
+}
+
+/// A response curve, mapping an input to a score from 0 to 1.
+impl Curve {
+
+    /// Apply the curve to an input.
+    pub fn apply(self, value: f32) -> f32 {
+        use Curve::*;
+        let value = value.clamp(0.0, 1.0);
+        match self {
+            Linear => value,
+            Inverse => 1.0 - value,
+            Square => value * value,
+            InverseSquare => (1.0 - value) * (1.0 - value),
+        }
+    }
+
+}
+
+/// One factor weighing on a choice.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Consideration {
+    /// What is measured.
+    pub input: Input,
+    /// How the measurement is scored.
+    pub curve: Curve,
+}
+
+/// One factor weighing on a choice.
+impl Consideration {
+
+    /// Constructor.
+    pub fn new(input: Input, curve: Curve) -> Consideration {
+        Consideration {
+            input,
+            curve,
+        }
+    }
+
+}
+
+/// The measurements an agent takes of its situation before choosing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Context {
+    /// The nearest visible threat, if any.
+    pub threat_id: Option<usize>,
+    /// Where a threat was most recently seen, if one is remembered.
+    pub last_sighting: Option<Position>,
+    /// How far away the nearest visible threat is, from 0 to 1.
+    pub distance_to_threat: f32,
+    /// How much of its health the agent has left, from 0 to 1.
+    pub hit_point_fraction: f32,
+    /// How badly the agent needs to eat, from 0 to 1.
+    pub hunger: f32,
+    /// How badly the agent needs to drink, from 0 to 1.
+    pub thirst: f32,
+    /// How badly the agent needs to sleep, from 0 to 1.
+    pub fatigue: f32,
+    /// How brightly lit the agent's position is, from 0 to 1.
+    pub light_level: f32,
+    /// How much of its will to fight the agent has left, from 0 to 1.
+    pub morale: f32,
+}
+
+/// The measurements an agent takes of its situation before choosing.
+impl Context {
+
+    /// Measure the agent's situation, and recall what it has seen.
+    pub fn new(id: usize, game: &Game, memory: &Memory) -> Context {
+        let entity = &game.entities[id];
+        let position = entity.position;
+        let threat = position.and_then(|position| {
+            game.get_visible_enemies(id)
+                .into_iter()
+                .filter_map(|other_id| game.entities[other_id].position.map(|other_position| (other_id, position.distance_to(&other_position))))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(cmp::Ordering::Equal))
+        });
+        let hit_point_fraction = entity.body.as_ref().map_or(1.0, |body| {
+            body.current_hit_points as f32 / body.total_hit_points.max(1) as f32
+        });
+        let light_level = position.map_or(0.0, |position| {
+            game.light_map.get_intensity_at(position.x, position.y) as f32 / 255.0
+        });
+        Context {
+            threat_id: threat.map(|(threat_id, _)| threat_id),
+            last_sighting: memory.sightings
+                .iter()
+                .max_by_key(|sighting| sighting.turn)
+                .map(|sighting| sighting.position),
+            distance_to_threat: threat.map_or(1.0, |(_, distance)| distance / THREAT_RANGE).min(1.0),
+            hit_point_fraction,
+            hunger: entity.needs.as_ref().map_or(0.0, |needs| needs.get_fraction(NeedKind::Hunger)),
+            thirst: entity.needs.as_ref().map_or(0.0, |needs| needs.get_fraction(NeedKind::Thirst)),
+            fatigue: entity.needs.as_ref().map_or(0.0, |needs| needs.get_fraction(NeedKind::Fatigue)),
+            light_level: light_level.min(1.0),
+            morale: entity.morale.map_or(1.0, |morale| morale.get_fraction()),
+        }
+    }
+
+    /// Get the measurement of an input.
+    pub fn get(&self, input: Input) -> f32 {
+        use Input::*;
+        match input {
+            DistanceToThreat => self.distance_to_threat,
+            HitPointFraction => self.hit_point_fraction,
+            Hunger => self.hunger,
+            Thirst => self.thirst,
+            Fatigue => self.fatigue,
+            LightLevel => self.light_level,
+            Morale => self.morale,
+        }
+    }
+
+}
+
+/// The things a utility agent can choose to do.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Choice {
+    /// Attack an adjacent threat.
+    Attack,
+    /// Shoot a threat that is in range.
+    Shoot,
+    /// Move towards a threat.
+    Approach,
+    /// Move to surround a threat alongside packmates.
+    Flank,
+    /// Go to where a threat was last seen.
+    Hunt,
+    /// Run from every threat in sight.
+    Flee,
+    /// Eat or move towards a nearby edible corpse.
+    Scavenge,
+    /// Seek out food, keeping clear of the player.
+    Forage,
+    /// Drink from a puddle, or make for the nearest.
+    Drink,
+    /// Stay put, resting if nothing is around.
+    Rest,
+    /// Fall asleep until rested.
+    Sleep,
+    /// Take up a place in formation around the pack leader.
+    FollowLeader,
+    /// Walk in a random direction.
+    Wander,
+}
+
+/// The things a utility agent can choose to do.
+impl Choice {
+
+    /// The command carrying out this choice, if it is possible right now.
+    pub fn get_command(self, id: usize, game: &Game, context: &Context, roll: u32) -> Option<Command> {
+        use Choice::*;
+        let position = game.entities[id].position?;
+        let threat_position = context.threat_id.and_then(|threat_id| game.entities[threat_id].position);
+        match self {
+            Attack => {
+                let threat_position = threat_position?;
+                if position.distance_to(&threat_position) >= 2.0 {
+                    return None;
+                }
+                agent::command_to_attack(id, &threat_position, game)
+            },
+            Shoot => {
+                let threat_position = threat_position?;
+                let range = game.entities[id].get_stats().map_or(0, |stats| stats.range);
+                let distance = cmp::max((position.x - threat_position.x).abs(), (position.y - threat_position.y).abs());
+                if distance < 2 || distance > range || !combat::has_line_of_fire(game, id, &threat_position) {
+                    return None;
+                }
+                Some(Command::RangedAttack(threat_position))
+            },
+            Approach => agent::command_to_move_towards(id, &threat_position?, game),
+            Flank => {
+                let threat_position = threat_position?;
+                if position.distance_to(&threat_position) < 2.0 {
+                    return None;
+                }
+                agent::command_to_flank(id, &threat_position, game)
+            },
+            Hunt => {
+                let last_sighting = context.last_sighting?;
+                if position.distance_to(&last_sighting) < 1.0 {
+                    return None;
+                }
+                agent::command_to_move_towards(id, &last_sighting, game)
+            },
+            Flee => agent::command_to_flee(id, &game.get_visible_threats(id), game),
+            Scavenge => agent::command_to_scavenge(id, game),
+            Forage => agent::command_to_follow(id, DijkstraMapKind::Forage, game),
+            Drink => agent::command_to_drink(id, game),
+            Rest => Some(Command::Wait),
+            Sleep => {
+                if context.threat_id.is_some() {
+                    return None;
+                }
+                Some(Command::Sleep)
+            },
+            FollowLeader => agent::command_to_follow_leader(id, game),
+            Wander => Some(Command::Walk(COMPASS_DIRECTIONS[roll as usize % COMPASS_DIRECTIONS.len()])),
+        }
+    }
+
+}
+
+/// A choice, and the considerations weighing on it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Desire {
+    /// The choice.
+    pub choice: Choice,
+    /// The most the choice can score.
+    pub weight: f32,
+    /// The considerations, whose scores are multiplied together.
+    pub considerations: Vec<Consideration>,
+}
+
+/// A choice, and the considerations weighing on it.
+impl Desire {
+
+    /// Constructor.
+    pub fn new(choice: Choice, weight: f32, considerations: Vec<Consideration>) -> Desire {
+        Desire {
+            choice,
+            weight,
+            considerations,
+        }
+    }
+
+    /// Score this desire in the given context.
+    pub fn score(&self, context: &Context) -> f32 {
+        self.considerations
+            .iter()
+            .map(|consideration| consideration.curve.apply(context.get(consideration.input)))
+            .fold(self.weight, |score, factor| score * factor)
+    }
+
+}
+
+/// The temperaments of utility agents.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Profile {
+    /// Keeps to the dark, fights when healthy and bolts when its nerve goes.
+    Skulker,
+}
+
+/// The temperaments of utility agents.
+impl Profile {
+
+    /// The desires weighed by agents of this temperament.
+    pub fn get_desires(self) -> Vec<Desire> {
+        use Choice::*;
+        use Curve::*;
+        use Input::*;
+        match self {
+            Profile::Skulker => vec![
+                Desire::new(Attack, 1.0, vec![
+                    Consideration::new(HitPointFraction, Linear),
+                ]),
+                Desire::new(Shoot, 0.9, vec![
+                    Consideration::new(HitPointFraction, Linear),
+                ]),
+                Desire::new(Approach, 0.7, vec![
+                    Consideration::new(HitPointFraction, Linear),
+                    Consideration::new(LightLevel, Inverse),
+                ]),
+                Desire::new(Flank, 0.75, vec![
+                    Consideration::new(HitPointFraction, Linear),
+                    Consideration::new(LightLevel, Inverse),
+                ]),
+                Desire::new(Hunt, 0.4, vec![
+                    Consideration::new(HitPointFraction, Linear),
+                    Consideration::new(Morale, Linear),
+                ]),
+                Desire::new(Flee, 1.0, vec![
+                    Consideration::new(Morale, InverseSquare),
+                    Consideration::new(DistanceToThreat, Inverse),
+                ]),
+                Desire::new(Scavenge, 0.8, vec![
+                    Consideration::new(Hunger, Linear),
+                    Consideration::new(DistanceToThreat, Linear),
+                ]),
+                Desire::new(Forage, 0.5, vec![
+                    Consideration::new(Hunger, Linear),
+                    Consideration::new(DistanceToThreat, Square),
+                ]),
+                Desire::new(Drink, 0.6, vec![
+                    Consideration::new(Thirst, Linear),
+                    Consideration::new(DistanceToThreat, Square),
+                ]),
+                Desire::new(Rest, 0.3, vec![
+                    Consideration::new(HitPointFraction, Inverse),
+                    Consideration::new(DistanceToThreat, Square),
+                ]),
+                Desire::new(Sleep, 1.0, vec![
+                    Consideration::new(Fatigue, Square),
+                    Consideration::new(DistanceToThreat, Square),
+                ]),
+                Desire::new(FollowLeader, 0.35, vec![
+                    Consideration::new(DistanceToThreat, Square),
+                ]),
+                Desire::new(Wander, 0.1, vec![]),
+            ],
+        }
+    }
+
+}
+
+/// Choose the best-scoring possible command for the agent.
+pub fn get_command(profile: Profile, time: i32, id: usize, game: &Game, memory: &Memory) -> Option<Command> {
+    trace!("Entering utility::get_command() for id {}.", id);
+    let context = Context::new(id, game, memory);
+    let seed = game.turns as i64 * 1024 + i64::from(time);
+    let mut best: Option<(f32, Command)> = None;
+    for (index, desire) in profile.get_desires().iter().enumerate() {
+        let roll = noise::hash(id as u32, seed * 16 + index as i64);
+        let score = desire.score(&context) + roll as f32 / u32::MAX as f32 * NOISE;
+        if best.is_some_and(|(best_score, _)| best_score >= score) {
+            continue;
+        }
+        if let Some(command) = desire.choice.get_command(id, game, &context, roll) {
+            debug!("Entity {} considers {:?} ({}).", game.entities[id].name, desire.choice, score);
+            best = Some((score, command));
+        }
+    }
+    trace!("Exiting utility::get_command().");
+    best.map(|(_, command)| command)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Curves keep their scores between 0 and 1.
+    #[test]
+    fn curves() {
+        assert_eq!(Curve::Linear.apply(0.25), 0.25);
+        assert_eq!(Curve::Inverse.apply(0.25), 0.75);
+        assert_eq!(Curve::Square.apply(0.5), 0.25);
+        assert_eq!(Curve::InverseSquare.apply(0.5), 0.25);
+        assert_eq!(Curve::Linear.apply(2.0), 1.0);
+        assert_eq!(Curve::Inverse.apply(-1.0), 1.0);
+    }
+
+    /// A wounded skulker near a threat would rather flee than attack.
+    #[test]
+    fn scores() {
+        let desires = Profile::Skulker.get_desires();
+        let get_score = |context: &Context, choice: Choice| {
+            desires.iter().find(|desire| desire.choice == choice).unwrap().score(context)
+        };
+        let healthy = Context {
+            threat_id: Some(0),
+            last_sighting: None,
+            distance_to_threat: 0.1,
+            hit_point_fraction: 1.0,
+            hunger: 0.0,
+            thirst: 0.0,
+            fatigue: 0.0,
+            light_level: 0.5,
+            morale: 1.0,
+        };
+        assert!(get_score(&healthy, Choice::Attack) > get_score(&healthy, Choice::Flee));
+        assert_eq!(get_score(&healthy, Choice::Scavenge), 0.0);
+        assert_eq!(get_score(&healthy, Choice::Drink), 0.0);
+        let wounded = Context {
+            hit_point_fraction: 0.2,
+            hunger: 0.8,
+            morale: 0.2,
+            ..healthy
+        };
+        assert!(get_score(&wounded, Choice::Flee) > get_score(&wounded, Choice::Attack));
+        assert!(get_score(&wounded, Choice::Flee) > get_score(&wounded, Choice::Scavenge));
+        let weary = Context {
+            threat_id: None,
+            distance_to_threat: 1.0,
+            fatigue: 0.8,
+            ..healthy
+        };
+        assert!(get_score(&weary, Choice::Sleep) > get_score(&weary, Choice::Wander));
+        assert!(get_score(&weary, Choice::Sleep) > get_score(&weary, Choice::FollowLeader));
+    }
+
+}
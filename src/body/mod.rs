@@ -1,8 +1,426 @@
+/// The factor by which resting speeds up regeneration.
+const RESTING_MULTIPLIER: usize = 3;
+
+/// The kinds of part from which bodies are built.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PartKind {
+    /// A head.
+    Head,
+    /// A torso.
+    Torso,
+    /// An arm.
+    Arm,
+    /// A leg.
+    Leg,
+    /// An eye.
+    Eye,
+    /// A wing.
+    Wing,
+    /// An undifferentiated mass, e.g. of moss.
+    Mass,
+}
+
+/// A single part of a body.
+#[derive(Clone, Debug)]
+pub struct Part {
+    /// The name of this part, e.g. "left leg".
+    pub name: String,
+    /// The kind of this part.
+    pub kind: PartKind,
+    /// The index of the part to which this part is attached, if any.
+    pub parent: Option<usize>,
+    /// The relative likelihood of this part being hit.
+    pub size: i32,
+    /// Whether destroying this part kills the body.
+    pub is_vital: bool,
+    /// The total hit points of this part.
+    pub total_hit_points: i32,
+    /// The current hit points of this part.
+    pub current_hit_points: i32,
+}
+
+/// A single part of a body.
+impl Part {
+
+    /// Indicates whether this part has been destroyed.
+    pub fn is_destroyed(&self) -> bool {
+        self.current_hit_points <= 0
+    }
+
+}
+
+/// The consequences of a wound.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Wound {
+    /// The part was damaged, but still works.
+    Damaged,
+    /// The part was destroyed.
+    Destroyed(PartKind),
+    /// A vital part was destroyed, or the body ran out of hit points.
+    Fatal,
+}
+
 /// A body is the physical representation of an actor entity.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Body {
     /// The total hit points that this body has.
     pub total_hit_points: i32,
     /// The current hit points that this body has.
     pub current_hit_points: i32,
+    /// The parts from which this body is built, as a tree.
+    pub parts: Vec<Part>,
+    /// The number of turns for which this body will continue to bleed.
+    pub bleeding: i32,
+    /// The number of turns it takes to regenerate a hit point, if any.
+    pub regeneration: Option<usize>,
+    /// Whether this body has rested (waited undisturbed) during this turn.
+    pub is_resting: bool,
+}
+
+/// A body is the physical representation of an actor entity.
+impl Body {
+
+    /// Indicates whether a part and everything it's attached to is intact.
+    pub fn is_functional(&self, index: usize) -> bool {
+        let mut current = Some(index);
+        while let Some(index) = current {
+            let part = &self.parts[index];
+            if part.is_destroyed() {
+                return false;
+            }
+            current = part.parent;
+        }
+        true
+    }
+
+    /// The number of parts of a kind, and the number of those that function.
+    pub fn count_parts(&self, kind: PartKind) -> (usize, usize) {
+        let indices = self.parts
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| part.kind == kind)
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>();
+        let functional = indices
+            .iter()
+            .filter(|&&index| self.is_functional(index))
+            .count();
+        (indices.len(), functional)
+    }
+
+    /// Scale a quantity by the fraction of functional parts of a kind.
+    ///
+    /// Bodies without any parts of the kind are unaffected.
+    pub fn scale_by_parts(&self, kind: PartKind, value: i32, minimum: i32) -> i32 {
+        let (total, functional) = self.count_parts(kind);
+        if total == 0 {
+            return value;
+        }
+        (value * functional as i32 / total as i32).max(minimum)
+    }
+
+    /// Pick a part to be hit, weighted by size, given a roll in [0, total size).
+    pub fn get_part_for_roll(&self, roll: i32) -> Option<usize> {
+        let mut remaining = roll;
+        for (index, part) in self.parts.iter().enumerate() {
+            if part.is_destroyed() {
+                continue;
+            }
+            if remaining < part.size {
+                return Some(index);
+            }
+            remaining -= part.size;
+        }
+        None
+    }
+
+    /// The total size of all intact parts.
+    pub fn get_target_size(&self) -> i32 {
+        self.parts
+            .iter()
+            .filter(|part| !part.is_destroyed())
+            .map(|part| part.size)
+            .sum()
+    }
+
+    /// Damage the body as a whole, e.g. from bleeding or poison.
+    pub fn damage(&mut self, hit_points: i32) -> Option<Wound> {
+        self.current_hit_points -= hit_points;
+        if self.current_hit_points <= 0 {
+            return Some(Wound::Fatal);
+        }
+        None
+    }
+
+    /// Damage a specific part, which also damages the body as a whole.
+    ///
+    /// Every wound that does damage starts (or prolongs) bleeding.
+    pub fn wound(&mut self, index: usize, hit_points: i32) -> Option<Wound> {
+        if hit_points <= 0 {
+            return None;
+        }
+        self.bleeding += hit_points / 2;
+        let was_destroyed = self.parts[index].is_destroyed();
+        self.parts[index].current_hit_points -= hit_points;
+        let part = &self.parts[index];
+        if part.is_destroyed() && part.is_vital {
+            self.current_hit_points = 0;
+            return Some(Wound::Fatal);
+        }
+        let kind = part.kind;
+        let is_destroyed = part.is_destroyed();
+        if let Some(Wound::Fatal) = self.damage(hit_points) {
+            return Some(Wound::Fatal);
+        }
+        if is_destroyed && !was_destroyed {
+            Some(Wound::Destroyed(kind))
+        } else {
+            Some(Wound::Damaged)
+        }
+    }
+
+    /// Increase the total hit points of the body, and of its parts in proportion.
+    pub fn grow(&mut self, hit_points: i32) {
+        let total_hit_points = self.total_hit_points.max(1);
+        for part in self.parts.iter_mut() {
+            let bonus = hit_points * part.total_hit_points / total_hit_points;
+            part.total_hit_points += bonus;
+            part.current_hit_points += bonus;
+        }
+        self.total_hit_points += hit_points;
+        self.current_hit_points += hit_points;
+    }
+
+    /// Return a modified version of this body.
+    pub fn with_regeneration(self, regeneration: Option<usize>) -> Body {
+        Body {
+            regeneration,
+            ..self
+        }
+    }
+
+    /// Restore hit points to the body and to every part that isn't destroyed.
+    ///
+    /// Healing also staunches bleeding.  Destroyed parts don't grow back.
+    pub fn heal(&mut self, hit_points: i32) {
+        if hit_points <= 0 {
+            return;
+        }
+        self.current_hit_points = (self.current_hit_points + hit_points).min(self.total_hit_points);
+        for part in self.parts.iter_mut().filter(|part| !part.is_destroyed()) {
+            part.current_hit_points = (part.current_hit_points + hit_points).min(part.total_hit_points);
+        }
+        self.bleeding = (self.bleeding - hit_points).max(0);
+    }
+
+    /// Regenerate for a turn; returns the hit points to restore.
+    ///
+    /// Bleeding bodies don't regenerate, and resting bodies regenerate faster.
+    pub fn regenerate(&mut self, turns: usize) -> i32 {
+        let is_resting = self.is_resting;
+        self.is_resting = false;
+        let regeneration = match self.regeneration {
+            Some(regeneration) if regeneration > 0 => regeneration,
+            _ => return 0,
+        };
+        if self.bleeding > 0 || self.current_hit_points >= self.total_hit_points {
+            return 0;
+        }
+        let interval = if is_resting {
+            (regeneration / RESTING_MULTIPLIER).max(1)
+        } else {
+            regeneration
+        };
+        if turns.is_multiple_of(interval) {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Bleed for a turn; returns the damage to apply.
+    pub fn bleed(&mut self) -> i32 {
+        if self.bleeding > 0 {
+            self.bleeding -= 1;
+            1
+        } else {
+            0
+        }
+    }
+
+}
+
+/// A factory for bodies of different shapes.
+#[derive(Clone, Copy, Debug)]
+pub enum Factory {
+    /// Head, torso, two arms, two legs, two eyes.
+    Humanoid,
+    /// Head, torso, two wings, two legs, two eyes.
+    Avian,
+    /// A single undifferentiated mass.
+    Mass,
+}
+
+/// A factory for bodies of different shapes.
+impl Factory {
+
+    /// Create a body with the specified total hit points.
+    ///
+    /// Each part's hit points are proportional to its size.
+    pub fn create(self, hit_points: i32) -> Body {
+        use PartKind::*;
+        // (name, kind, parent, size, is_vital)
+        let plan: Vec<(&str, PartKind, Option<usize>, i32, bool)> = match self {
+            Factory::Humanoid => vec![
+                ("torso", Torso, None, 6, true),
+                ("head", Head, Some(0), 2, true),
+                ("left arm", Arm, Some(0), 2, false),
+                ("right arm", Arm, Some(0), 2, false),
+                ("left leg", Leg, Some(0), 3, false),
+                ("right leg", Leg, Some(0), 3, false),
+                ("left eye", Eye, Some(1), 1, false),
+                ("right eye", Eye, Some(1), 1, false),
+            ],
+            Factory::Avian => vec![
+                ("body", Torso, None, 6, true),
+                ("head", Head, Some(0), 2, true),
+                ("left wing", Wing, Some(0), 3, false),
+                ("right wing", Wing, Some(0), 3, false),
+                ("left leg", Leg, Some(0), 2, false),
+                ("right leg", Leg, Some(0), 2, false),
+                ("left eye", Eye, Some(1), 1, false),
+                ("right eye", Eye, Some(1), 1, false),
+            ],
+            Factory::Mass => vec![
+                ("mass", Mass, None, 1, true),
+            ],
+        };
+        let parts = plan
+            .into_iter()
+            .map(|(name, kind, parent, size, is_vital)| {
+                let part_hit_points = match kind {
+                    Torso | Mass => hit_points,
+                    _ => (hit_points * size / 6).max(1),
+                };
+                Part {
+                    name: name.to_string(),
+                    kind,
+                    parent,
+                    size,
+                    is_vital,
+                    total_hit_points: part_hit_points,
+                    current_hit_points: part_hit_points,
+                }
+            })
+            .collect();
+        Body {
+            total_hit_points: hit_points,
+            current_hit_points: hit_points,
+            parts,
+            bleeding: 0,
+            regeneration: None,
+            is_resting: false,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Destroying a leg leaves the body alive, but less mobile.
+    #[test]
+    fn destroy_leg() {
+        let mut body = Factory::Humanoid.create(30);
+        assert_eq!(12, body.scale_by_parts(PartKind::Leg, 12, 1));
+        let wound = body.wound(4, 100);
+        assert_eq!(Some(Wound::Fatal), wound);
+        let mut body = Factory::Humanoid.create(30);
+        let leg_hit_points = body.parts[4].total_hit_points;
+        assert_eq!(Some(Wound::Destroyed(PartKind::Leg)), body.wound(4, leg_hit_points));
+        assert_eq!(6, body.scale_by_parts(PartKind::Leg, 12, 1));
+    }
+
+    /// Destroying a vital part is fatal.
+    #[test]
+    fn destroy_head() {
+        let mut body = Factory::Humanoid.create(30);
+        let head_hit_points = body.parts[1].total_hit_points;
+        assert_eq!(Some(Wound::Fatal), body.wound(1, head_hit_points));
+    }
+
+    /// Eyes stop working when the head is destroyed.
+    #[test]
+    fn functional() {
+        let mut body = Factory::Humanoid.create(30);
+        assert_eq!((2, 2), body.count_parts(PartKind::Eye));
+        body.parts[1].current_hit_points = 0;
+        assert_eq!((2, 0), body.count_parts(PartKind::Eye));
+    }
+
+    /// Parts are picked in proportion to their size.
+    #[test]
+    fn get_part_for_roll() {
+        let body = Factory::Humanoid.create(30);
+        assert_eq!(20, body.get_target_size());
+        assert_eq!(Some(0), body.get_part_for_roll(0));
+        assert_eq!(Some(0), body.get_part_for_roll(5));
+        assert_eq!(Some(1), body.get_part_for_roll(6));
+        assert_eq!(Some(7), body.get_part_for_roll(19));
+        assert_eq!(None, body.get_part_for_roll(20));
+    }
+
+    /// Healing is capped, and doesn't restore destroyed parts.
+    #[test]
+    fn heal() {
+        let mut body = Factory::Humanoid.create(30);
+        let leg_hit_points = body.parts[4].total_hit_points;
+        body.wound(4, leg_hit_points);
+        body.wound(2, 2);
+        body.heal(100);
+        assert_eq!(30, body.current_hit_points);
+        assert_eq!(0, body.bleeding);
+        assert!(body.parts[4].is_destroyed());
+        assert_eq!(body.parts[2].total_hit_points, body.parts[2].current_hit_points);
+    }
+
+    /// Resting speeds up regeneration, and bleeding stops it.
+    #[test]
+    fn regenerate() {
+        let mut body = Factory::Humanoid.create(30).with_regeneration(Some(9));
+        assert_eq!(0, body.regenerate(3));
+        body.current_hit_points = 20;
+        assert_eq!(0, body.regenerate(3));
+        assert_eq!(1, body.regenerate(9));
+        body.is_resting = true;
+        assert_eq!(1, body.regenerate(3));
+        assert!(!body.is_resting);
+        body.bleeding = 1;
+        assert_eq!(0, body.regenerate(9));
+    }
+
+    /// Growing adds hit points to the body and its parts.
+    #[test]
+    fn grow() {
+        let mut body = Factory::Humanoid.create(30);
+        body.grow(6);
+        assert_eq!(36, body.total_hit_points);
+        assert_eq!(36, body.current_hit_points);
+        assert_eq!(36, body.parts[0].total_hit_points);
+        assert_eq!(12, body.parts[1].total_hit_points);
+    }
+
+    /// Wounds cause bleeding, which stops over time.
+    #[test]
+    fn bleed() {
+        let mut body = Factory::Humanoid.create(30);
+        body.wound(2, 4);
+        assert_eq!(2, body.bleeding);
+        assert_eq!(1, body.bleed());
+        assert_eq!(1, body.bleed());
+        assert_eq!(0, body.bleed());
+    }
+
 }
@@ -1,3 +1,28 @@
+use rand::Rng;
+use crate::combat;
+use combat::dice::Dice;
+use crate::game;
+use game::Game;
+
+/// The roll (on a d20) at or above which an attack is a critical hit.
+const CRITICAL_ROLL: i32 = 20;
+
+/// The roll (on a d20) at or below which an attack always misses.
+const FUMBLE_ROLL: i32 = 1;
+
+/// The base difficulty of hitting a target with no defense.
+const BASE_DIFFICULTY: i32 = 10;
+
+/// The outcome of an attack.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// The attack missed.
+    Miss,
+    /// The attack hit a body part (if the target has any), dealing some damage.
+    Hit(Option<usize>, i32),
+    /// The attack hit a body part especially well, dealing some damage.
+    CriticalHit(Option<usize>, i32),
+}
 
 /// An attack context.
 #[derive(Clone, Copy, Debug)]
@@ -6,4 +31,157 @@ pub struct Context {
     pub attacker_id: usize,
     /// The entity ID of the defender.
     pub defender_id: usize,
+    /// A bonus (or penalty) to the attack roll, e.g. for range.
+    pub modifier: i32,
+    /// The damage dealt, if not the attacker's unarmed damage.
+    pub damage: Option<Dice>,
+}
+
+/// An attack context.
+impl Context {
+
+    /// Constructor.
+    pub fn new(attacker_id: usize, defender_id: usize) -> Context {
+        Context {
+            attacker_id,
+            defender_id,
+            modifier: 0,
+            damage: None,
+        }
+    }
+
+    /// Return a modified version of this context.
+    pub fn with_modifier(&self, modifier: i32) -> Context {
+        Context {
+            modifier,
+            ..*self
+        }
+    }
+
+    /// Return a modified version of this context.
+    pub fn with_damage(&self, damage: Dice) -> Context {
+        Context {
+            damage: Some(damage),
+            ..*self
+        }
+    }
+
+    /// Resolve the attack, rolling with the game's seeded RNG.
+    ///
+    /// A d20 plus the attacker's attack must meet 10 plus the defender's
+    /// defense.  A natural 20 is a critical hit, doubling the damage dice; a
+    /// natural 1 always misses.  Armor is subtracted from the damage.  The
+    /// part struck is chosen at random, weighted by the size of each part.
+    pub fn resolve(&self, game: &mut Game) -> Outcome {
+        trace!("Entering Context::resolve().");
+        let attacker_stats = game.entities[self.attacker_id].get_stats().unwrap_or_default();
+        let defender_stats = game.entities[self.defender_id].get_stats().unwrap_or_default();
+        let roll = game.rng.gen_range(1, 21);
+        let attack = attacker_stats.attack + self.modifier;
+        debug!("Attack roll: {} + {} vs {} + {}.", roll, attack, BASE_DIFFICULTY, defender_stats.defense);
+        if roll <= FUMBLE_ROLL {
+            return Outcome::Miss;
+        }
+        let is_critical = roll >= CRITICAL_ROLL;
+        if !is_critical && roll + attack < BASE_DIFFICULTY + defender_stats.defense {
+            return Outcome::Miss;
+        }
+        let dice = self.damage.unwrap_or(attacker_stats.damage);
+        let mut damage = dice.roll(&mut game.rng);
+        if is_critical {
+            damage += dice.roll(&mut game.rng);
+        }
+        let damage = (damage - defender_stats.armor).max(0);
+        let target_size = game.entities[self.defender_id].body
+            .as_ref()
+            .map_or(0, |body| body.get_target_size());
+        let part = if target_size > 0 {
+            let roll = game.rng.gen_range(0, target_size);
+            game.entities[self.defender_id].body
+                .as_ref()
+                .and_then(|body| body.get_part_for_roll(roll))
+        } else {
+            None
+        };
+        trace!("Exiting Context::resolve().");
+        if is_critical {
+            Outcome::CriticalHit(part, damage)
+        } else {
+            Outcome::Hit(part, damage)
+        }
+    }
+
+    /// Describe the outcome of the attack.
+    pub fn describe(&self, outcome: Outcome, game: &Game) -> String {
+        let attacker = &game.entities[self.attacker_id].name;
+        let defender = &game.entities[self.defender_id].name;
+        let get_location = |part: Option<usize>| {
+            let body = game.entities[self.defender_id].body.as_ref();
+            match (body, part) {
+                (Some(body), Some(index)) => format!(" in the {}", body.parts[index].name),
+                _ => String::new(),
+            }
+        };
+        match outcome {
+            Outcome::Miss => format!("{} misses {}.", attacker, defender),
+            Outcome::Hit(part, 0) => format!("{} hits {}{}, but does no damage.", attacker, defender, get_location(part)),
+            Outcome::Hit(part, damage) => format!("{} hits {}{} for {} damage.", attacker, defender, get_location(part), damage),
+            Outcome::CriticalHit(part, damage) => format!("{} critically hits {}{} for {} damage!", attacker, defender, get_location(part), damage),
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::component;
+    use component::stats::Stats;
+    use crate::entity;
+    use entity::Entity;
+
+    /// A game with an attacker (0) and a defender (1).
+    fn get_game(attack: i32, defense: i32) -> Game {
+        let mut game = game::get_test_game(10, 10);
+        for (id, stats) in [Stats::new(attack, 0, 0, Dice::new(2, 1, 0)), Stats::new(0, defense, 0, Dice::new(1, 1, 0))].iter().enumerate() {
+            let mut entity = Entity::new(format!("Entity {}", id));
+            entity.id = id;
+            entity.stats = Some(*stats);
+            game.entities.push(entity);
+        }
+        game
+    }
+
+    /// A d20 plus attack must meet 10 plus defense; a natural 1 always misses and a natural 20 always crits.
+    #[test]
+    fn resolve() {
+        let (attack, defense) = (2, 3);
+        let mut game = get_game(attack, defense);
+        let context = Context::new(0, 1);
+        for _ in 0..200 {
+            let roll = game.rng.clone().gen_range(1, 21);
+            let expected = if roll == FUMBLE_ROLL {
+                Outcome::Miss
+            } else if roll == CRITICAL_ROLL {
+                Outcome::CriticalHit(None, 4)
+            } else if roll + attack >= BASE_DIFFICULTY + defense {
+                Outcome::Hit(None, 2)
+            } else {
+                Outcome::Miss
+            };
+            assert_eq!(context.resolve(&mut game), expected, "roll {}", roll);
+        }
+    }
+
+    /// Nothing but a natural 20 hits a target out of reach, and a crit doubles the damage dice.
+    #[test]
+    fn critical() {
+        let mut game = get_game(0, 20);
+        let context = Context::new(0, 1);
+        let outcomes = (0..200).map(|_| context.resolve(&mut game)).collect::<Vec<_>>();
+        assert!(outcomes.contains(&Outcome::CriticalHit(None, 4)));
+        assert!(outcomes.iter().all(|&outcome| outcome == Outcome::Miss || outcome == Outcome::CriticalHit(None, 4)));
+    }
+
 }
@@ -0,0 +1,74 @@
+use std::fmt;
+use rand::Rng;
+
+/// A handful of dice, e.g. 2d6+1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Dice {
+    /// The number of dice.
+    pub count: i32,
+    /// The number of sides on each die.
+    pub sides: i32,
+    /// A flat bonus added to the total.
+    pub bonus: i32,
+}
+
+/// A handful of dice, e.g. 2d6+1.
+impl Dice {
+
+    /// Constructor.
+    pub fn new(count: i32, sides: i32, bonus: i32) -> Dice {
+        Dice {
+            count,
+            sides,
+            bonus,
+        }
+    }
+
+    /// Roll the dice.
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> i32 {
+        let mut total = self.bonus;
+        for _ in 0..self.count {
+            total += rng.gen_range(1, self.sides.max(1) + 1);
+        }
+        total
+    }
+
+}
+
+/// Displays the dice in the conventional notation.
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.bonus {
+            0 => write!(f, "{}d{}", self.count, self.sides),
+            bonus if bonus > 0 => write!(f, "{}d{}+{}", self.count, self.sides, bonus),
+            bonus => write!(f, "{}d{}{}", self.count, self.sides, bonus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::seed::get_rng;
+
+    /// Rolls stay within the possible range.
+    #[test]
+    fn roll() {
+        let mut rng = get_rng([7; 32]);
+        let dice = Dice::new(2, 6, 1);
+        for _ in 0..1000 {
+            let result = dice.roll(&mut rng);
+            assert!(result >= 3 && result <= 13);
+        }
+    }
+
+    /// Notation.
+    #[test]
+    fn display() {
+        assert_eq!("2d6+1", format!("{}", Dice::new(2, 6, 1)));
+        assert_eq!("1d8", format!("{}", Dice::new(1, 8, 0)));
+        assert_eq!("1d4-1", format!("{}", Dice::new(1, 4, -1)));
+    }
+
+}
@@ -1,18 +1,189 @@
+use bear_lib_terminal::Color;
+use crate::component;
+use component::position::Position;
 use crate::effect;
 use effect::Effect;
+use crate::event;
+use event::Event;
 use crate::game;
 use game::Game;
+use crate::math;
+use math::line;
 
 /// Attack.
 pub mod attack;
+use attack::{Context, Outcome};
 
-/// Attack.
-pub fn attack(attacker_id: usize, target_id: usize, game: &mut Game) {
-    println!("Entering attack() with attacker {} and target {}.", attacker_id, target_id);
-    let _context = attack::Context {
-        attacker_id: attacker_id,
-        defender_id: target_id,
+/// Dice.
+pub mod dice;
+
+/// Projectiles.
+pub mod projectile;
+use projectile::Projectile;
+
+/// Resolve an attack, reporting it if the player is watching.
+///
+/// Returns the effect of the attack, if any.
+pub fn attack(attacker_id: usize, target_id: usize, game: &mut Game) -> Option<Effect> {
+    trace!("Entering attack() with attacker {} and target {}.", attacker_id, target_id);
+    let context = Context::new(attacker_id, target_id);
+    let outcome = context.resolve(game);
+    report(&context, outcome, game);
+    Event::Attack(attacker_id, target_id).publish(game);
+    trace!("Exiting attack().");
+    get_effect(&context, outcome)
+}
+
+/// Launch a projectile towards a position, reporting it if the player is watching.
+///
+/// The projectile flies along a line through the position until it runs out
+/// of range or strikes a blocking tile.  Each creature in its path may be
+/// hit; a projectile that misses flies on.
+pub fn fire(attacker_id: usize, target: Position, projectile: Projectile, game: &mut Game) -> Option<Effect> {
+    trace!("Entering fire() with attacker {} and target {:?}.", attacker_id, target);
+    let origin = game.entities[attacker_id].position?;
+    let stats = game.entities[attacker_id].get_stats().unwrap_or_default();
+    let range = projectile.get_range(&stats);
+    let ray = line::get_ray(origin.x, origin.y, target.x, target.y, range.max(0) as usize);
+    for (distance, &(x, y)) in ray.iter().enumerate() {
+        if !game.map.is_in_bounds(x as usize, y as usize) || game.map.get_tile(x as usize, y as usize).blocks_movement {
+            break;
+        }
+        let defender_id = game.get_entities(x, y)
+            .iter()
+            .filter(|entity| entity.id != attacker_id && entity.body.is_some() && entity.blocks_movement)
+            .map(|entity| entity.id)
+            .next();
+        if let Some(defender_id) = defender_id {
+            let context = Context::new(attacker_id, defender_id)
+                .with_modifier(projectile.get_modifier(distance as i32 + 1))
+                .with_damage(projectile.get_damage(&stats));
+            let outcome = context.resolve(game);
+            report(&context, outcome, game);
+            Event::Attack(attacker_id, defender_id).publish(game);
+            if outcome != Outcome::Miss {
+                trace!("Exiting fire().");
+                return get_effect(&context, outcome);
+            }
+        }
+    }
+    trace!("Exiting fire().");
+    None
+}
+
+/// Indicates whether an entity has a clear line of fire to a position.
+///
+/// Only tiles and creatures between the entity and the position count.
+pub fn has_line_of_fire(game: &Game, id: usize, target: &Position) -> bool {
+    let origin = match game.entities[id].position {
+        Some(position) => position,
+        None => return false,
     };
-    Effect::DamageEntityBody(target_id, 7).execute(attacker_id, game);
-    println!("Exiting attack().");
+    let line = line::get_line(origin.x, origin.y, target.x, target.y);
+    if line.len() < 2 {
+        return false;
+    }
+    line[1..line.len() - 1]
+        .iter()
+        .all(|&(x, y)| {
+            !game.map.get_tile(x as usize, y as usize).blocks_movement
+                && !game.get_entities(x, y).iter().any(|entity| entity.body.is_some() && entity.blocks_movement)
+        })
+}
+
+/// Report the outcome of an attack, if the player can see either party.
+fn report(context: &Context, outcome: Outcome, game: &mut Game) {
+    let player_id = game.player_id;
+    if game.can_see_entity(player_id, context.attacker_id) || game.can_see_entity(player_id, context.defender_id) {
+        let color = match outcome {
+            Outcome::Miss => Color::from_rgb(160, 160, 160),
+            Outcome::Hit(_, _) => Color::from_rgb(255, 255, 255),
+            Outcome::CriticalHit(_, _) => Color::from_rgb(255, 96, 96),
+        };
+        let message = context.describe(outcome, game);
+        game.add_message(message, color);
+    }
+}
+
+/// The effect of an attack's outcome.
+fn get_effect(context: &Context, outcome: Outcome) -> Option<Effect> {
+    let target_id = context.defender_id;
+    match outcome {
+        Outcome::Miss => None,
+        Outcome::Hit(_, 0) | Outcome::CriticalHit(_, 0) => None,
+        Outcome::Hit(Some(part), damage) | Outcome::CriticalHit(Some(part), damage) => Some(Effect::WoundEntityBodyPart(target_id, part, damage)),
+        Outcome::Hit(None, damage) | Outcome::CriticalHit(None, damage) => Some(Effect::DamageEntityBody(target_id, damage)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::Rng;
+    use crate::body;
+    use body::Factory as BodyFactory;
+    use component::stats::Stats;
+    use crate::entity;
+    use entity::Entity;
+    use dice::Dice;
+    use crate::tile;
+    use tile::Tile;
+
+    /// Place a creature on the map of a test game.
+    fn spawn(game: &mut Game, x: i32, y: i32) -> usize {
+        let id = game.entities.len();
+        let mut entity = Entity::new(format!("Entity {}", id));
+        entity.id = id;
+        entity.body = Some(BodyFactory::Humanoid.create(10));
+        entity.stats = Some(Stats::new(100, 0, 0, Dice::new(1, 1, 0)));
+        entity.position = Some(Position::new(game.seed, x, y, 0));
+        entity.blocks_movement = true;
+        game.entities.push(entity);
+        game.map.insert_entity(id, x as usize, y as usize);
+        id
+    }
+
+    /// Skip the rolls that would fumble, so that the next attack hits.
+    fn skip_fumbles(game: &mut Game) {
+        while game.rng.clone().gen_range(1, 21) == 1 {
+            game.rng.gen_range(1, 21);
+        }
+    }
+
+    /// Walls and creatures in between block a line of fire; the ends do not.
+    #[test]
+    fn has_line_of_fire() {
+        let mut game = game::get_test_game(12, 12);
+        let shooter_id = spawn(&mut game, 1, 5);
+        let target_id = spawn(&mut game, 6, 5);
+        let target = game.entities[target_id].position.unwrap();
+        assert!(super::has_line_of_fire(&game, shooter_id, &target));
+        game.map.set_tile(3, 5, Tile::wall());
+        assert!(!super::has_line_of_fire(&game, shooter_id, &target));
+        game.map.set_tile(3, 5, Tile::floor());
+        spawn(&mut game, 4, 5);
+        assert!(!super::has_line_of_fire(&game, shooter_id, &target));
+    }
+
+    /// A projectile stops at a wall, and strikes the first creature in its path.
+    #[test]
+    fn fire() {
+        let mut game = game::get_test_game(12, 12);
+        let shooter_id = spawn(&mut game, 1, 5);
+        let target_id = spawn(&mut game, 6, 5);
+        let target = game.entities[target_id].position.unwrap();
+        game.map.set_tile(3, 5, Tile::wall());
+        for _ in 0..20 {
+            assert!(super::fire(shooter_id, target, Projectile::Thrown, &mut game).is_none());
+        }
+        game.map.set_tile(3, 5, Tile::floor());
+        let occupant_id = spawn(&mut game, 4, 5);
+        skip_fumbles(&mut game);
+        match super::fire(shooter_id, target, Projectile::Thrown, &mut game) {
+            Some(Effect::WoundEntityBodyPart(id, _, _)) | Some(Effect::DamageEntityBody(id, _)) => assert_eq!(id, occupant_id),
+            effect => panic!("expected a hit, got {:?}", effect),
+        }
+    }
+
 }
@@ -0,0 +1,47 @@
+use crate::combat;
+use combat::dice::Dice;
+use crate::component;
+use component::stats::Stats;
+
+/// The range at which anyone can throw something.
+pub const THROW_RANGE: i32 = 6;
+
+/// The number of cells of flight that cost a point of attack.
+const RANGE_PENALTY_STEP: i32 = 3;
+
+/// Things that can be shot or thrown.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Projectile {
+    /// Something shot from a bow or sling.
+    Missile,
+    /// Something thrown by hand.
+    Thrown,
+}
+
+/// Things that can be shot or thrown.
+impl Projectile {
+
+    /// The range of this projectile when launched by an entity with these stats.
+    pub fn get_range(self, stats: &Stats) -> i32 {
+        use Projectile::*;
+        match self {
+            Missile => stats.range,
+            Thrown => THROW_RANGE,
+        }
+    }
+
+    /// The damage dealt by this projectile when launched by an entity with these stats.
+    pub fn get_damage(self, stats: &Stats) -> Dice {
+        use Projectile::*;
+        match self {
+            Missile => stats.ranged_damage,
+            Thrown => Dice::new(1, 3, 0),
+        }
+    }
+
+    /// The attack modifier for a projectile that has flown this many cells.
+    pub fn get_modifier(self, distance: i32) -> i32 {
+        -(distance / RANGE_PENALTY_STEP)
+    }
+
+}
@@ -2,15 +2,23 @@ use rand::Rng;
 use rand::distributions::{Distribution, Standard};
 use crate::action;
 use action::Action;
+use crate::combat;
+use combat::projectile::THROW_RANGE;
 use crate::component;
+use component::consumable::Kind as ConsumableKind;
 use component::position::Position;
+use component::status::Kind as StatusKind;
+use crate::faction;
+use faction::Penalty;
 use crate::game;
 use game::Game;
+use crate::math;
+use math::noise;
 use crate::species;
 use species::Species;
 
 /// Compass directions.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum CompassDirection {
     /// North.
     North,
@@ -30,6 +38,18 @@ pub enum CompassDirection {
     West,
 }
 
+/// All compass directions.
+pub const COMPASS_DIRECTIONS: [CompassDirection; 8] = [
+    CompassDirection::North,
+    CompassDirection::Northeast,
+    CompassDirection::Northwest,
+    CompassDirection::South,
+    CompassDirection::Southeast,
+    CompassDirection::Southwest,
+    CompassDirection::East,
+    CompassDirection::West,
+];
+
 impl Distribution<CompassDirection> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> CompassDirection {
         match rng.gen_range(0, 8) {
@@ -52,10 +72,50 @@ pub enum Command {
     Walk(CompassDirection),
     /// Attack (melee) something in the specified direction.
     MeleeAttack(CompassDirection),
+    /// Shoot at the specified position.
+    RangedAttack(Position),
+    /// Throw something at the specified position.
+    Throw(Position),
     /// Just wait, wasting a turn.
     Wait,
     /// Stall -- don't waste turn, but don't do anything.
     Stall,
+    /// Start or stop sneaking.
+    ToggleSneak,
+    /// Eat a corpse at the entity's position.
+    Eat,
+    /// Graze on moss at the entity's position.
+    Graze,
+    /// Drink from the water at the entity's position.
+    Drink,
+    /// Fall asleep until rested.
+    Sleep,
+    /// Pick up an item at the entity's position.
+    PickUp,
+    /// Drop the specified carried item.
+    Drop(usize),
+    /// List the items carried.
+    ListInventory,
+    /// Wield or wear the specified carried item.
+    Equip(usize),
+    /// Stop wielding or wearing the specified item.
+    Unequip(usize),
+    /// Drink the specified carried potion.
+    Quaff(usize),
+    /// Read the specified carried scroll.
+    Read(usize),
+    /// Eat the specified carried food.
+    EatItem(usize),
+    /// Open (or search) the specified container.
+    Open(usize),
+    /// Take the specified item from the specified container.
+    TakeFrom(usize, usize),
+    /// Put the specified carried item into the specified container.
+    PutInto(usize, usize),
+    /// Call on the entity's pack for help against the specified threat.
+    CallForHelp(usize),
+    /// Mushroom: Release spores onto whatever is touching it.
+    ReleaseSpores,
     /// Moss: Bloom,
     MossBloom,
     /// Moss: Seed,
@@ -78,12 +138,72 @@ impl Command {
             MeleeAttack(compass_direction) => {
                 Some(Action::MeleeAttack(compass_direction))
             }
+            RangedAttack(position) => {
+                Some(Action::RangedAttack(position))
+            },
+            Throw(position) => {
+                Some(Action::Throw(position))
+            },
             Wait => {
                 Some(Action::Wait)
             },
             Stall => {
                 Some(Action::Stall)
             },
+            ToggleSneak => {
+                Some(Action::ToggleSneak)
+            },
+            Eat => {
+                Some(Action::Eat)
+            },
+            Graze => {
+                Some(Action::Graze)
+            },
+            Drink => {
+                Some(Action::Drink)
+            },
+            Sleep => {
+                Some(Action::Sleep)
+            },
+            PickUp => {
+                Some(Action::PickUp)
+            },
+            Drop(item_id) => {
+                Some(Action::Drop(item_id))
+            },
+            ListInventory => {
+                Some(Action::ListInventory)
+            },
+            Equip(item_id) => {
+                Some(Action::Equip(item_id))
+            },
+            Unequip(item_id) => {
+                Some(Action::Unequip(item_id))
+            },
+            Quaff(item_id) => {
+                Some(Action::Consume(item_id))
+            },
+            Read(item_id) => {
+                Some(Action::Consume(item_id))
+            },
+            EatItem(item_id) => {
+                Some(Action::Consume(item_id))
+            },
+            Open(container_id) => {
+                Some(Action::Open(container_id))
+            },
+            TakeFrom(container_id, item_id) => {
+                Some(Action::TakeFrom(container_id, item_id))
+            },
+            PutInto(container_id, item_id) => {
+                Some(Action::PutInto(container_id, item_id))
+            },
+            CallForHelp(threat_id) => {
+                Some(Action::CallForHelp(threat_id))
+            },
+            ReleaseSpores => {
+                Some(Action::ReleaseSpores)
+            },
             MossBloom => {
                 Some(Action::MossBloom)
             },
@@ -134,6 +254,21 @@ impl Command {
                     ]
                 }
             },
+            RangedAttack(position) => {
+                let range = game.entities[id].get_stats().map_or(0, |stats| stats.range);
+                vec![
+                    PositionIsNotOutOfBounds(position),
+                    PositionIsNotOwnPosition(position),
+                    PositionIsWithinRange(position, range),
+                ]
+            },
+            Throw(position) => {
+                vec![
+                    PositionIsNotOutOfBounds(position),
+                    PositionIsNotOwnPosition(position),
+                    PositionIsWithinRange(position, THROW_RANGE),
+                ]
+            },
             Wait => {
                 vec![
                     Permit,
@@ -144,6 +279,143 @@ impl Command {
                     Permit,
                 ]
             },
+            ToggleSneak => {
+                if game.entities[id].perception.is_some() {
+                    vec![
+                        Permit,
+                    ]
+                } else {
+                    vec![
+                        Deny("Entity is incapable of sneaking!".to_string()),
+                    ]
+                }
+            },
+            Eat => {
+                if let Some(position) = game.entities[id].position {
+                    vec![
+                        SomethingAtPositionIsEdible(position),
+                    ]
+                } else {
+                    vec![
+                        Deny("Entity has no starting position!".to_string()),
+                    ]
+                }
+            },
+            Graze => {
+                if let Some(position) = game.entities[id].position {
+                    vec![
+                        SomethingAtPositionIsOfSpecies(position, Species::Moss),
+                    ]
+                } else {
+                    vec![
+                        Deny("Entity has no starting position!".to_string()),
+                    ]
+                }
+            },
+            Drink => {
+                if let Some(position) = game.entities[id].position {
+                    vec![
+                        TileAtPositionIsDrinkable(position),
+                    ]
+                } else {
+                    vec![
+                        Deny("Entity has no starting position!".to_string()),
+                    ]
+                }
+            },
+            Sleep => {
+                vec![
+                    Permit,
+                ]
+            },
+            PickUp => {
+                if let Some(position) = game.entities[id].position {
+                    vec![
+                        EntityHasInventory,
+                        SomethingAtPositionCanBePickedUp(position),
+                    ]
+                } else {
+                    vec![
+                        Deny("Entity has no starting position!".to_string()),
+                    ]
+                }
+            },
+            Drop(item_id) => {
+                vec![
+                    EntityHasInventory,
+                    EntityIsCarryingItem(item_id),
+                ]
+            },
+            ListInventory => {
+                vec![
+                    EntityHasInventory,
+                ]
+            },
+            Equip(item_id) => {
+                vec![
+                    EntityHasInventory,
+                    EntityIsCarryingItem(item_id),
+                    ItemIsEquippable(item_id),
+                ]
+            },
+            Unequip(item_id) => {
+                vec![
+                    ItemIsEquipped(item_id),
+                ]
+            },
+            Quaff(item_id) => {
+                vec![
+                    EntityHasInventory,
+                    EntityIsCarryingItem(item_id),
+                    ItemIsConsumableAs(item_id, ConsumableKind::Potion),
+                ]
+            },
+            Read(item_id) => {
+                vec![
+                    EntityHasInventory,
+                    EntityIsCarryingItem(item_id),
+                    ItemIsConsumableAs(item_id, ConsumableKind::Scroll),
+                    EntityIsNotConfusedWhileReading,
+                ]
+            },
+            EatItem(item_id) => {
+                vec![
+                    EntityHasInventory,
+                    EntityIsCarryingItem(item_id),
+                    ItemIsConsumableAs(item_id, ConsumableKind::Food),
+                ]
+            },
+            Open(container_id) => {
+                vec![
+                    ContainerIsWithinReach(container_id),
+                ]
+            },
+            TakeFrom(container_id, item_id) => {
+                vec![
+                    EntityHasInventory,
+                    ContainerIsWithinReach(container_id),
+                    ContainerHoldsItem(container_id, item_id),
+                    EntityCanCarryItem(item_id),
+                ]
+            },
+            PutInto(container_id, item_id) => {
+                vec![
+                    EntityHasInventory,
+                    ContainerIsWithinReach(container_id),
+                    EntityIsCarryingItem(item_id),
+                    ContainerCanHoldItem(container_id, item_id),
+                ]
+            },
+            CallForHelp(_) => {
+                vec![
+                    EntityIsInPack,
+                ]
+            },
+            ReleaseSpores => {
+                vec![
+                    Permit,
+                ]
+            },
             MossBloom => {
                 vec![
                     Permit,
@@ -174,11 +446,53 @@ impl Command {
         }
     }
 
+    /// List the preconditions imposed on this command by the entity's statuses.
+    pub fn get_status_preconditions(self, id: usize, game: &Game) -> Vec<CommandPrecondition> {
+        trace!("Entering Command::get_status_preconditions() for command {:?}.", self);
+        use Command::*;
+        use CommandPrecondition::*;
+        if game.entities[id].statuses.is_none() {
+            return vec![];
+        }
+        match self {
+            Wait | Stall | ListInventory => vec![],
+            Walk(compass_direction) => {
+                vec![
+                    EntityIsNotIncapacitated,
+                    EntityIsNotConfused(compass_direction),
+                ]
+            },
+            _ => {
+                vec![
+                    EntityIsNotIncapacitated,
+                ]
+            },
+        }
+    }
+
+    /// List the preconditions imposed on this command by the entity's faction penalties.
+    pub fn get_penalty_preconditions(self, id: usize, game: &Game) -> Vec<CommandPrecondition> {
+        trace!("Entering Command::get_penalty_preconditions() for command {:?}.", self);
+        use Command::*;
+        use CommandPrecondition::*;
+        if game.entities[id].faction_standings.is_none() {
+            return vec![];
+        }
+        match self {
+            Wait | Stall | ListInventory | Sleep => vec![],
+            _ => vec![EntityIsNotJailed],
+        }
+    }
+
     /// Check the preconditions for this command.
     pub fn check_preconditions(self, id: usize, game: &Game) -> CommandPreconditionResult {
         trace!("Entering Command::check_preconditions() for command {:?}.", self);
         use CommandPreconditionResult::*;
-        for precondition in self.get_preconditions(id, game) {
+        let preconditions = self.get_status_preconditions(id, game)
+            .into_iter()
+            .chain(self.get_penalty_preconditions(id, game))
+            .chain(self.get_preconditions(id, game));
+        for precondition in preconditions {
             debug!("Checking precondition {:?} for command {:?}.", precondition, self);
             let precondition_name = format!("{:?}", precondition);
             match precondition.evaluate(id, game) {
@@ -290,6 +604,8 @@ pub enum CommandPrecondition {
     PositionIsNotOutOfBounds(Position),
     /// Position does not block movement.
     TileAtPositionDoesNotBlockMovement(Position),
+    /// The tile at the position holds water fit to drink.
+    TileAtPositionIsDrinkable(Position),
     /// The positions are adjacent.
     PositionsAreAdjacent(Position, Position),
     /// No entity at the location blocks movement.
@@ -300,6 +616,48 @@ pub enum CommandPrecondition {
     SomethingAtPositionIsValidMeleeAttackTarget(Position),
     /// Don't seed where there's something of the same species.
     NothingAtPositionIsOfSpecies(Position, Species),
+    /// Something at the location is of the specified species.
+    SomethingAtPositionIsOfSpecies(Position, Species),
+    /// The position is not the entity's own position.
+    PositionIsNotOwnPosition(Position),
+    /// Something at the location can be eaten.
+    SomethingAtPositionIsEdible(Position),
+    /// The entity is able to carry items.
+    EntityHasInventory,
+    /// Something at the location can be picked up and carried by the entity.
+    SomethingAtPositionCanBePickedUp(Position),
+    /// The entity is carrying the specified item.
+    EntityIsCarryingItem(usize),
+    /// The item can be wielded or worn by the entity.
+    ItemIsEquippable(usize),
+    /// The entity is wielding or wearing the specified item.
+    ItemIsEquipped(usize),
+    /// The container is at or adjacent to the entity's position.
+    ContainerIsWithinReach(usize),
+    /// The container holds the specified item.
+    ContainerHoldsItem(usize, usize),
+    /// The entity has room to carry the specified item.
+    EntityCanCarryItem(usize),
+    /// The container has room for the specified item.
+    ContainerCanHoldItem(usize, usize),
+    /// The item is consumed in the specified way.
+    ItemIsConsumableAs(usize, ConsumableKind),
+    /// The entity is not too confused to read.
+    EntityIsNotConfusedWhileReading,
+    /// The entity is not stunned or asleep; otherwise it just waits.
+    ///
+    /// Substituting a wait is deliberate: a denied command only stalls the
+    /// player, which takes no time, so an incapacitated player would never
+    /// see the status wear off.  Waiting spends the turn for everyone alike.
+    EntityIsNotIncapacitated,
+    /// The entity is not confused; otherwise it may stagger in another direction.
+    EntityIsNotConfused(CompassDirection),
+    /// The entity is not jailed by any faction; otherwise it just waits.
+    EntityIsNotJailed,
+    /// The position is within the specified range of the entity.
+    PositionIsWithinRange(Position, i32),
+    /// The entity belongs to a pack.
+    EntityIsInPack,
 }
 
 
@@ -332,6 +690,13 @@ impl CommandPrecondition {
                 }
                 Neutral
             },
+            TileAtPositionIsDrinkable(position) => {
+                trace!("Entering precondition {:?}.", TileAtPositionIsDrinkable(position));
+                if !game.map.get_tile_at_position(&position).is_drinkable {
+                    return Denied("There is nothing here to drink.".to_string());
+                }
+                Neutral
+            },
             PositionsAreAdjacent(position1, position2) => {
                 trace!("Entering precondition {:?}.", PositionsAreAdjacent(position1, position2));
                 if (position1.x - position2.x).abs() > 1 || (position1.y - position2.y).abs() > 1 {
@@ -391,6 +756,194 @@ impl CommandPrecondition {
                 debug!("Did not find entities of undesired species {:?} at position {:?}.", bad_species, position);
                 Neutral
             },
+            SomethingAtPositionIsOfSpecies(position, wanted_species) => {
+                trace!("Entering precondition {:?}.", SomethingAtPositionIsOfSpecies(position, wanted_species));
+                let is_found = game.get_entities(position.x, position.y)
+                    .iter()
+                    .any(|entity| entity.species == Some(wanted_species));
+                if !is_found {
+                    return Denied(format!("Found no entity of species {:?} at position {:?}.", wanted_species, position));
+                }
+                Neutral
+            },
+            SomethingAtPositionIsEdible(position) => {
+                trace!("Entering precondition {:?}.", SomethingAtPositionIsEdible(position));
+                let is_edible = game.get_entities(position.x, position.y)
+                    .iter()
+                    .any(|entity| entity.corpse.as_ref().is_some_and(|corpse| corpse.is_edible()));
+                if !is_edible {
+                    return Denied("There is nothing here to eat.".to_string());
+                }
+                Neutral
+            },
+            EntityHasInventory => {
+                trace!("Entering precondition {:?}.", EntityHasInventory);
+                if game.entities[id].inventory.is_none() {
+                    return Denied("Entity is incapable of carrying things!".to_string());
+                }
+                Neutral
+            },
+            SomethingAtPositionCanBePickedUp(position) => {
+                trace!("Entering precondition {:?}.", SomethingAtPositionCanBePickedUp(position));
+                let items = game.get_items(position.x, position.y);
+                if items.is_empty() {
+                    return Denied("There is nothing here to pick up.".to_string());
+                }
+                if !items.iter().any(|item| game.can_carry(id, item.id)) {
+                    return Denied("You cannot carry any more.".to_string());
+                }
+                Neutral
+            },
+            EntityIsCarryingItem(item_id) => {
+                trace!("Entering precondition {:?}.", EntityIsCarryingItem(item_id));
+                let is_carrying = game.entities[id].inventory
+                    .as_ref()
+                    .is_some_and(|inventory| inventory.contains(item_id));
+                if !is_carrying {
+                    return Denied("You are not carrying that.".to_string());
+                }
+                Neutral
+            },
+            ItemIsEquippable(item_id) => {
+                trace!("Entering precondition {:?}.", ItemIsEquippable(item_id));
+                if game.entities[id].equipment.is_none() {
+                    return Denied("Entity is incapable of wielding or wearing things!".to_string());
+                }
+                if game.entities[item_id].equippable.is_none() {
+                    return Denied("You cannot wield or wear that.".to_string());
+                }
+                Neutral
+            },
+            ItemIsEquipped(item_id) => {
+                trace!("Entering precondition {:?}.", ItemIsEquipped(item_id));
+                let is_equipped = game.entities[id].equipment
+                    .as_ref()
+                    .is_some_and(|equipment| equipment.contains(item_id));
+                if !is_equipped {
+                    return Denied("You are not wielding or wearing that.".to_string());
+                }
+                Neutral
+            },
+            ContainerIsWithinReach(container_id) => {
+                trace!("Entering precondition {:?}.", ContainerIsWithinReach(container_id));
+                if game.entities[container_id].container.is_none() {
+                    return Denied("That is not a container.".to_string());
+                }
+                if !game.can_reach(id, container_id) {
+                    return Denied("The container is out of reach.".to_string());
+                }
+                Neutral
+            },
+            ContainerHoldsItem(container_id, item_id) => {
+                trace!("Entering precondition {:?}.", ContainerHoldsItem(container_id, item_id));
+                let holds_item = game.entities[container_id].inventory
+                    .as_ref()
+                    .is_some_and(|inventory| inventory.contains(item_id));
+                if !holds_item {
+                    return Denied("The container does not hold that.".to_string());
+                }
+                Neutral
+            },
+            EntityCanCarryItem(item_id) => {
+                trace!("Entering precondition {:?}.", EntityCanCarryItem(item_id));
+                if !game.can_carry(id, item_id) {
+                    return Denied("You cannot carry any more.".to_string());
+                }
+                Neutral
+            },
+            ContainerCanHoldItem(container_id, item_id) => {
+                trace!("Entering precondition {:?}.", ContainerCanHoldItem(container_id, item_id));
+                if !game.can_carry(container_id, item_id) {
+                    return Denied("There is no room for that.".to_string());
+                }
+                Neutral
+            },
+            ItemIsConsumableAs(item_id, kind) => {
+                trace!("Entering precondition {:?}.", ItemIsConsumableAs(item_id, kind));
+                match game.entities[item_id].consumable {
+                    Some(consumable) if consumable.kind == kind => Neutral,
+                    _ => Denied(format!("You cannot {} that.", kind.get_verb())),
+                }
+            },
+            EntityIsNotConfusedWhileReading => {
+                trace!("Entering precondition {:?}.", EntityIsNotConfusedWhileReading);
+                if game.entities[id].has_status(StatusKind::Confused) {
+                    return Denied("You are too confused to read.".to_string());
+                }
+                Neutral
+            },
+            EntityIsNotJailed => {
+                trace!("Entering precondition {:?}.", EntityIsNotJailed);
+                let is_jailed = game.entities[id].faction_standings
+                    .as_ref()
+                    .is_some_and(|standings| standings.iter().any(|standing| matches!(standing.penalty, Some(Penalty::Jail(_)))));
+                if is_jailed {
+                    debug!("Entity {} is jailed.", game.entities[id].name);
+                    return Substituted(Command::Wait);
+                }
+                Neutral
+            },
+            EntityIsNotIncapacitated => {
+                trace!("Entering precondition {:?}.", EntityIsNotIncapacitated);
+                let entity = &game.entities[id];
+                if entity.statuses.as_ref().is_some_and(|statuses| statuses.is_incapacitated()) {
+                    debug!("Entity {} is incapacitated.", entity.name);
+                    return Substituted(Command::Wait);
+                }
+                Neutral
+            },
+            EntityIsNotConfused(compass_direction) => {
+                trace!("Entering precondition {:?}.", EntityIsNotConfused(compass_direction));
+                let entity = &game.entities[id];
+                if !entity.has_status(StatusKind::Confused) {
+                    return Neutral;
+                }
+                // Stagger deterministically for this entity and moment, so that
+                // re-checking the substituted command settles on the same direction.
+                let time = entity.actor.map_or(0, |actor| actor.time);
+                let hash = noise::hash(id as u32, game.turns as i64 * 1024 + i64::from(time));
+                if hash.is_multiple_of(2) {
+                    return Neutral;
+                }
+                let stagger = COMPASS_DIRECTIONS[(hash / 2) as usize % COMPASS_DIRECTIONS.len()];
+                if stagger == compass_direction {
+                    return Neutral;
+                }
+                if let Some(position) = entity.position {
+                    let destination = position.to_direction(stagger);
+                    if !game.map.is_position_in_bounds(&destination) || game.map.get_tile_at_position(&destination).blocks_movement {
+                        return Neutral;
+                    }
+                }
+                debug!("Entity {} staggers {:?} instead of {:?}.", entity.name, stagger, compass_direction);
+                Substituted(Command::Walk(stagger))
+            },
+            PositionIsNotOwnPosition(position) => {
+                trace!("Entering precondition {:?}.", PositionIsNotOwnPosition(position));
+                if let Some(entity_position) = game.entities[id].position {
+                    if entity_position.x == position.x && entity_position.y == position.y {
+                        return Denied("The target position is the entity's own position.".to_string());
+                    }
+                }
+                Neutral
+            },
+            PositionIsWithinRange(position, range) => {
+                trace!("Entering precondition {:?}.", PositionIsWithinRange(position, range));
+                if let Some(entity_position) = game.entities[id].position {
+                    if (entity_position.x - position.x).abs() > range || (entity_position.y - position.y).abs() > range {
+                        return Denied("The target position is out of range.".to_string());
+                    }
+                    return Neutral;
+                }
+                Denied("Entity has no starting position!".to_string())
+            },
+            EntityIsInPack => {
+                trace!("Entering precondition {:?}.", EntityIsInPack);
+                if game.entities[id].group.is_none() {
+                    return Denied("The entity has no pack to call on.".to_string());
+                }
+                Neutral
+            },
         }
     }
 
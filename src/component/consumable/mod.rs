@@ -0,0 +1,105 @@
+use crate::effect;
+use effect::Effect;
+use crate::component;
+use component::status::Kind as StatusKind;
+
+/// The ways in which something can be consumed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// Drunk.
+    Potion,
+    /// Read aloud.
+    Scroll,
+    /// Eaten.
+    Food,
+}
+
+/// The ways in which something can be consumed.
+impl Kind {
+
+    /// The verb describing consuming something of this kind.
+    pub fn get_verb(self) -> &'static str {
+        use Kind::*;
+        match self {
+            Potion => "quaff",
+            Scroll => "read",
+            Food => "eat",
+        }
+    }
+
+}
+
+/// What happens when something is consumed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum UseEffect {
+    /// Heals the consumer.
+    Healing,
+    /// Moves the consumer somewhere else at random.
+    Teleportation,
+    /// Reveals the layout of the map.
+    MagicMapping,
+    /// Lights up the surrounding area for a while.
+    Light,
+    /// Grows moss all around the consumer.
+    Moss,
+    /// Nourishes the consumer by the specified amount.
+    Nourishment(i32),
+    /// Poisons the consumer for the specified number of turns.
+    Poison(i32),
+}
+
+/// What happens when something is consumed.
+impl UseEffect {
+
+    /// The name of this use effect, as revealed on identification.
+    pub fn get_name(self) -> &'static str {
+        use UseEffect::*;
+        match self {
+            Healing => "healing",
+            Teleportation => "teleportation",
+            MagicMapping => "magic mapping",
+            Light => "light",
+            Moss => "moss",
+            Nourishment(_) => "nourishment",
+            Poison(_) => "poison",
+        }
+    }
+
+    /// The effect on the consumer.
+    pub fn get_effect(self, id: usize) -> Effect {
+        use UseEffect::*;
+        match self {
+            Healing => Effect::HealEntityBody(id, 15),
+            Teleportation => Effect::TeleportEntity,
+            MagicMapping => Effect::RevealMap,
+            Light => Effect::LightBurst,
+            Moss => Effect::SummonMoss,
+            Nourishment(amount) => Effect::Nourish(amount),
+            Poison(duration) => Effect::InflictStatus(id, StatusKind::Poisoned, duration),
+        }
+    }
+
+}
+
+/// Something that can be used up.
+#[derive(Clone, Copy, Debug)]
+pub struct Consumable {
+    /// How this is consumed.
+    pub kind: Kind,
+    /// What happens when this is consumed.
+    pub use_effect: UseEffect,
+}
+
+/// Something that can be used up.
+impl Consumable {
+
+    /// Constructor.
+    pub fn new(kind: Kind, use_effect: UseEffect) -> Consumable {
+        trace!("Entering Consumable::new().");
+        Consumable {
+            kind,
+            use_effect,
+        }
+    }
+
+}
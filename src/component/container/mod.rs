@@ -0,0 +1,45 @@
+/// The kinds of container.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Kind {
+    /// A sturdy wooden chest.
+    Chest,
+    /// A cloth sack.
+    Sack,
+    /// The remains of a creature, and whatever it carried.
+    Corpse,
+}
+
+/// The kinds of container.
+impl Kind {
+
+    /// The verb describing opening this kind of container.
+    pub fn get_verb(self) -> &'static str {
+        use Kind::*;
+        match self {
+            Chest => "open",
+            Sack => "open",
+            Corpse => "search",
+        }
+    }
+
+}
+
+/// Something that holds items, which are kept in its inventory.
+#[derive(Clone, Copy, Debug)]
+pub struct Container {
+    /// The kind of container.
+    pub kind: Kind,
+}
+
+/// Something that holds items, which are kept in its inventory.
+impl Container {
+
+    /// Constructor.
+    pub fn new(kind: Kind) -> Container {
+        trace!("Entering Container::new().");
+        Container {
+            kind,
+        }
+    }
+
+}
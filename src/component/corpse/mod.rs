@@ -0,0 +1,147 @@
+use bear_lib_terminal::Color;
+use crate::species;
+use species::Species;
+
+/// The age at which a corpse starts to rot.
+const ROTTING_AGE: i32 = 150;
+
+/// The age at which a corpse is reduced to bones.
+const SKELETAL_AGE: i32 = 400;
+
+/// The age at which a corpse rots away entirely.
+const ROTTED_AGE: i32 = 800;
+
+/// The stages through which a corpse decays.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Stage {
+    /// Recently killed.
+    Fresh,
+    /// Starting to rot.
+    Rotting,
+    /// Nothing left but bones.
+    Skeletal,
+    /// Nothing left at all.
+    Rotted,
+}
+
+/// The stages through which a corpse decays.
+impl Stage {
+
+    /// The name of a corpse of the specified creature at this stage.
+    pub fn get_name(self, name: &str) -> String {
+        use Stage::*;
+        match self {
+            Fresh => format!("{} corpse", name),
+            Rotting => format!("rotting {} corpse", name),
+            Skeletal | Rotted => format!("{} skeleton", name),
+        }
+    }
+
+    /// The hit points restored by eating a corpse at this stage.
+    pub fn get_nutrition(self) -> i32 {
+        use Stage::*;
+        match self {
+            Fresh => 3,
+            Rotting => 1,
+            Skeletal | Rotted => 0,
+        }
+    }
+
+    /// Indicates whether eating a corpse at this stage is poisonous.
+    pub fn is_tainted(self) -> bool {
+        self == Stage::Rotting
+    }
+
+    /// The color of a corpse at this stage.
+    pub fn get_color(self) -> Color {
+        use Stage::*;
+        match self {
+            Fresh => Color::from_rgb(192, 32, 32),
+            Rotting => Color::from_rgb(112, 128, 32),
+            Skeletal | Rotted => Color::from_rgb(216, 216, 192),
+        }
+    }
+
+}
+
+/// The remains of a dead creature.
+#[derive(Clone, Debug)]
+pub struct Corpse {
+    /// The species of the creature.
+    pub species: Option<Species>,
+    /// The name of the creature.
+    pub name: String,
+    /// The number of turns since the creature died.
+    pub age: i32,
+}
+
+/// The remains of a dead creature.
+impl Corpse {
+
+    /// Constructor.
+    pub fn new(species: Option<Species>, name: String) -> Corpse {
+        trace!("Entering Corpse::new().");
+        Corpse {
+            species,
+            name,
+            age: 0,
+        }
+    }
+
+    /// The current stage of decay.
+    pub fn get_stage(&self) -> Stage {
+        use Stage::*;
+        if self.age >= ROTTED_AGE {
+            Rotted
+        } else if self.age >= SKELETAL_AGE {
+            Skeletal
+        } else if self.age >= ROTTING_AGE {
+            Rotting
+        } else {
+            Fresh
+        }
+    }
+
+    /// Decay for a turn; returns the new stage if it changed.
+    pub fn decay(&mut self) -> Option<Stage> {
+        let stage = self.get_stage();
+        self.age += 1;
+        let new_stage = self.get_stage();
+        if new_stage != stage {
+            Some(new_stage)
+        } else {
+            None
+        }
+    }
+
+    /// Indicates whether anything is left to eat.
+    pub fn is_edible(&self) -> bool {
+        match self.get_stage() {
+            Stage::Fresh | Stage::Rotting => true,
+            Stage::Skeletal | Stage::Rotted => false,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Corpses decay through each stage in order.
+    #[test]
+    fn decay() {
+        let mut corpse = Corpse::new(Some(Species::Orc), "Orc".to_string());
+        let mut stages = vec![];
+        for _ in 0..ROTTED_AGE {
+            if let Some(stage) = corpse.decay() {
+                stages.push(stage);
+            }
+        }
+        assert_eq!(vec![Stage::Rotting, Stage::Skeletal, Stage::Rotted], stages);
+        assert!(!corpse.is_edible());
+        assert_eq!("Orc skeleton", corpse.get_stage().get_name(&corpse.name));
+    }
+
+}
@@ -0,0 +1,218 @@
+/// The places in which equipment can be wielded or worn.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Slot {
+    /// The main hand, for weapons.
+    MainHand,
+    /// The off hand, for torches and shields.
+    OffHand,
+    /// The body, for armor.
+    Body,
+    /// The head, for helmets.
+    Head,
+}
+
+/// The places in which equipment can be wielded or worn.
+impl Slot {
+
+    /// The name of this slot.
+    pub fn get_name(self) -> &'static str {
+        use Slot::*;
+        match self {
+            MainHand => "main hand",
+            OffHand => "off hand",
+            Body => "body",
+            Head => "head",
+        }
+    }
+
+    /// The verb describing putting something in this slot.
+    pub fn get_verb(self) -> &'static str {
+        use Slot::*;
+        match self {
+            MainHand | OffHand => "wield",
+            Body | Head => "wear",
+        }
+    }
+
+}
+
+/// Something that can be wielded or worn, and how it modifies its holder.
+#[derive(Clone, Copy, Debug)]
+pub struct Equippable {
+    /// The slot this occupies.
+    pub slot: Slot,
+    /// The bonus to attack.
+    pub attack: i32,
+    /// The bonus to defense.
+    pub defense: i32,
+    /// The bonus to speed.
+    pub speed: i32,
+    /// The bonus to field-of-view radius.
+    pub fov_radius: i32,
+}
+
+/// Something that can be wielded or worn, and how it modifies its holder.
+impl Equippable {
+
+    /// Constructor.
+    pub fn new(slot: Slot) -> Equippable {
+        trace!("Entering Equippable::new().");
+        Equippable {
+            slot,
+            attack: 0,
+            defense: 0,
+            speed: 0,
+            fov_radius: 0,
+        }
+    }
+
+    /// Return a modified version of this equippable.
+    pub fn with_attack(&self, attack: i32) -> Equippable {
+        Equippable {
+            attack,
+            ..*self
+        }
+    }
+
+    /// Return a modified version of this equippable.
+    pub fn with_defense(&self, defense: i32) -> Equippable {
+        Equippable {
+            defense,
+            ..*self
+        }
+    }
+
+    /// Return a modified version of this equippable.
+    pub fn with_speed(&self, speed: i32) -> Equippable {
+        Equippable {
+            speed,
+            ..*self
+        }
+    }
+
+    /// Return a modified version of this equippable.
+    pub fn with_fov_radius(&self, fov_radius: i32) -> Equippable {
+        Equippable {
+            fov_radius,
+            ..*self
+        }
+    }
+
+}
+
+/// An item currently wielded or worn.
+#[derive(Clone, Copy, Debug)]
+pub struct Equipped {
+    /// The ID of the item.
+    pub item_id: usize,
+    /// How the item modifies its holder.
+    pub equippable: Equippable,
+    /// Whether the item's light source was lent to its holder.
+    pub is_lighting: bool,
+}
+
+/// The items an entity is currently wielding or wearing.
+#[derive(Clone, Debug)]
+pub struct Equipment {
+    /// The items equipped, at most one per slot.
+    pub entries: Vec<Equipped>,
+}
+
+/// The items an entity is currently wielding or wearing.
+impl Equipment {
+
+    /// Constructor.
+    pub fn new() -> Equipment {
+        trace!("Entering Equipment::new().");
+        Equipment {
+            entries: vec![],
+        }
+    }
+
+    /// The ID of the item in the specified slot, if any.
+    pub fn get(&self, slot: Slot) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|entry| entry.equippable.slot == slot)
+            .map(|entry| entry.item_id)
+    }
+
+    /// Indicates whether the specified item is equipped.
+    pub fn contains(&self, item_id: usize) -> bool {
+        self.entries.iter().any(|entry| entry.item_id == item_id)
+    }
+
+    /// Equip an item, replacing anything in the same slot.
+    pub fn equip(&mut self, equipped: Equipped) {
+        let slot = equipped.equippable.slot;
+        self.entries.retain(|entry| entry.equippable.slot != slot);
+        self.entries.push(equipped);
+    }
+
+    /// Remove an item, returning it if it was equipped.
+    pub fn remove(&mut self, item_id: usize) -> Option<Equipped> {
+        let index = self.entries.iter().position(|entry| entry.item_id == item_id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// The total bonus to attack.
+    pub fn get_attack(&self) -> i32 {
+        self.entries.iter().map(|entry| entry.equippable.attack).sum()
+    }
+
+    /// The total bonus to defense.
+    pub fn get_defense(&self) -> i32 {
+        self.entries.iter().map(|entry| entry.equippable.defense).sum()
+    }
+
+    /// The total bonus to speed.
+    pub fn get_speed(&self) -> i32 {
+        self.entries.iter().map(|entry| entry.equippable.speed).sum()
+    }
+
+    /// The total bonus to sight radius.
+    pub fn get_fov_radius(&self) -> i32 {
+        self.entries.iter().map(|entry| entry.equippable.fov_radius).sum()
+    }
+
+}
+
+/// Creates a default instance.
+impl Default for Equipment {
+
+    /// Creates a default instance.
+    fn default() -> Self {
+        Equipment::new()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Equipping replaces anything in the same slot, and bonuses add up.
+    #[test]
+    fn equip() {
+        let mut equipment = Equipment::new();
+        let dagger = Equippable::new(Slot::MainHand).with_attack(2);
+        let sword = Equippable::new(Slot::MainHand).with_attack(3);
+        let helmet = Equippable::new(Slot::Head).with_defense(1).with_fov_radius(-2);
+        equipment.equip(Equipped { item_id: 1, equippable: dagger, is_lighting: false });
+        equipment.equip(Equipped { item_id: 2, equippable: helmet, is_lighting: false });
+        assert_eq!(Some(1), equipment.get(Slot::MainHand));
+        assert_eq!(2, equipment.get_attack());
+        equipment.equip(Equipped { item_id: 3, equippable: sword, is_lighting: false });
+        assert!(!equipment.contains(1));
+        assert_eq!(3, equipment.get_attack());
+        assert_eq!(1, equipment.get_defense());
+        assert_eq!(-2, equipment.get_fov_radius());
+        assert!(equipment.remove(2).is_some());
+        assert!(equipment.remove(2).is_none());
+        assert_eq!(0, equipment.get_defense());
+        assert_eq!(0, equipment.get_fov_radius());
+        assert_eq!(None, equipment.get(Slot::OffHand));
+    }
+
+}
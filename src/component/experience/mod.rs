@@ -0,0 +1,108 @@
+/// The experience points needed per level, scaled quadratically.
+const POINTS_PER_LEVEL: i32 = 10;
+
+/// The ways in which an entity can grow stronger on gaining a level.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Growth {
+    /// Better chance to hit.
+    Attack,
+    /// Harder to hit.
+    Defense,
+    /// More hit points.
+    Vitality,
+}
+
+/// The ways in which an entity can grow stronger on gaining a level.
+impl Growth {
+
+    /// The growth automatically chosen by monsters on reaching a level.
+    pub fn for_level(level: i32) -> Growth {
+        use Growth::*;
+        match level % 3 {
+            0 => Attack,
+            1 => Defense,
+            _ => Vitality,
+        }
+    }
+
+    /// A description of this growth, for prompts.
+    pub fn describe(self) -> &'static str {
+        use Growth::*;
+        match self {
+            Attack => "+1 attack",
+            Defense => "+1 defense",
+            Vitality => "+5 maximum hit points",
+        }
+    }
+
+}
+
+/// An entity's accumulated experience and character level.
+#[derive(Clone, Copy, Debug)]
+pub struct Experience {
+    /// The current character level.
+    pub level: i32,
+    /// The total experience points accumulated.
+    pub points: i32,
+    /// Levels gained whose growth hasn't yet been chosen.
+    pub unspent: i32,
+}
+
+/// An entity's accumulated experience and character level.
+impl Experience {
+
+    /// Constructor.
+    pub fn new(level: i32) -> Experience {
+        trace!("Entering Experience::new().");
+        Experience {
+            level,
+            points: Experience::get_threshold(level),
+            unspent: 0,
+        }
+    }
+
+    /// The total experience points needed to reach the specified level.
+    pub fn get_threshold(level: i32) -> i32 {
+        POINTS_PER_LEVEL * level * (level - 1)
+    }
+
+    /// Gain experience points; returns the number of levels gained.
+    pub fn gain(&mut self, points: i32) -> i32 {
+        self.points += points.max(0);
+        let mut gained = 0;
+        while self.points >= Experience::get_threshold(self.level + 1) {
+            self.level += 1;
+            gained += 1;
+        }
+        self.unspent += gained;
+        gained
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Levels need progressively more experience.
+    #[test]
+    fn gain() {
+        let mut experience = Experience::new(1);
+        assert_eq!(0, experience.gain(19));
+        assert_eq!(1, experience.gain(1));
+        assert_eq!(2, experience.level);
+        assert_eq!(2, experience.gain(100));
+        assert_eq!(4, experience.level);
+        assert_eq!(3, experience.unspent);
+    }
+
+    /// Starting at a level implies the experience for it.
+    #[test]
+    fn new() {
+        let experience = Experience::new(3);
+        assert_eq!(60, experience.points);
+        assert_eq!(0, Experience::new(1).points);
+    }
+
+}
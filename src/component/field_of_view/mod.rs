@@ -10,6 +10,8 @@ pub struct FieldOfView {
     pub algorithm: FovAlgorithm,
     /// Radius.
     pub radius: i32,
+    /// The radius before any bonuses.
+    pub base_radius: i32,
     /// The FOV map.
     pub map: Arc<Mutex<Map>>,
     /// The explored areas of the map.
@@ -24,6 +26,8 @@ pub struct FieldOfView {
     pub width: i32,
     /// Full height of the map.
     pub height: i32,
+    /// Whether the underlying map has changed since the last computation.
+    pub dirty: bool,
 }
 
 /// Something representing an entity's ability to see the game world.
@@ -38,6 +42,7 @@ impl FieldOfView {
         FieldOfView {
             algorithm: FovAlgorithm::Basic,
             radius: radius,
+            base_radius: radius,
             map: Arc::new(Mutex::new(map)),
             explored_map: explored_map,
             light_walls: false,
@@ -45,27 +50,64 @@ impl FieldOfView {
             y: -1,
             width: width,
             height: height,
+            dirty: false,
         }
     }
 
     /// Indicates whether a pair of coordinates are in bounds of this map.
     pub fn is_in_bounds(&self, x: i32, y: i32) -> bool {
         trace!("Entering FieldOfView::is_in_bounds().");
-        (x >= 0 && y >= 0 && x < self.width - 1 && y < self.height - 1)
+        x >= 0 && y >= 0 && x < self.width - 1 && y < self.height - 1
+    }
+
+    /// Indicates whether a pair of coordinates falls within the last computed radius.
+    pub fn is_within_radius(&self, x: i32, y: i32) -> bool {
+        trace!("Entering FieldOfView::is_within_radius().");
+        if self.x < 0 || self.y < 0 {
+            return false;
+        }
+        (x - self.x).abs() <= self.radius && (y - self.y).abs() <= self.radius
+    }
+
+    /// Marks this field of view as requiring recomputation.
+    pub fn invalidate(&mut self) {
+        trace!("Entering FieldOfView::invalidate().");
+        self.dirty = true;
+    }
+
+    /// Recomputes the radius from the base radius and a bonus, which may be negative.
+    pub fn apply_radius_bonus(&mut self, bonus: i32) {
+        trace!("Entering FieldOfView::apply_radius_bonus().");
+        self.radius = (self.base_radius + bonus).max(1);
+        self.invalidate();
+    }
+
+    /// Updates a single cell of the FOV map after the game map has changed.
+    pub fn set_cell(&mut self, x: i32, y: i32, transparent: bool, walkable: bool) {
+        trace!("Entering FieldOfView::set_cell().");
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+        self.map.lock().unwrap().set(x, y, transparent, walkable);
+        if self.is_within_radius(x, y) {
+            self.invalidate();
+        }
+        trace!("Exiting FieldOfView::set_cell().");
     }
 
     /// Updates.
     pub fn update(&mut self, x: i32, y: i32) {
         trace!("Entering FieldOfView::update().");
-        if x == self.x && y == self.y {
+        if x == self.x && y == self.y && !self.dirty {
             return;
         }
         let map = &mut self.map.lock().unwrap();
         map.compute_fov(x, y, self.radius, self.light_walls, self.algorithm);
         self.x = x;
         self.y = y;
-        for y2 in (y - self.radius)..(y + self.radius) {
-            for x2 in (x - self.radius)..(x + self.radius) {
+        self.dirty = false;
+        for y2 in (y - self.radius)..=(y + self.radius) {
+            for x2 in (x - self.radius)..=(x + self.radius) {
                 if self.is_in_bounds(x2, y2) && !self.explored_map[x2 as usize][y2 as usize] && map.is_in_fov(x2, y2) {
                     self.explored_map[x2 as usize][y2 as usize] = true;
                 }
@@ -82,3 +124,74 @@ impl fmt::Debug for FieldOfView {
         write!(f, "FieldOfView")
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// An open field of view, with the viewer at (5, 5).
+    fn get_fov(radius: i32) -> FieldOfView {
+        let mut map = Map::new(20, 20);
+        for y in 0..20 {
+            for x in 0..20 {
+                map.set(x, y, true, true);
+            }
+        }
+        let mut fov = FieldOfView::new(map, radius);
+        fov.update(5, 5);
+        fov
+    }
+
+    /// Only cells within the radius of the last computation count.
+    #[test]
+    fn is_within_radius() {
+        let unplaced = FieldOfView::new(Map::new(20, 20), 3);
+        assert!(!unplaced.is_within_radius(0, 0));
+        let fov = get_fov(3);
+        assert!(fov.is_within_radius(5, 5));
+        assert!(fov.is_within_radius(8, 2));
+        assert!(!fov.is_within_radius(9, 5));
+        assert!(!fov.is_within_radius(5, 1));
+    }
+
+    /// Invalidating marks the field of view dirty, and updating cleans it.
+    #[test]
+    fn invalidate() {
+        let mut fov = get_fov(3);
+        assert!(!fov.dirty);
+        fov.invalidate();
+        assert!(fov.dirty);
+        fov.update(5, 5);
+        assert!(!fov.dirty);
+        assert_eq!((fov.x, fov.y), (5, 5));
+    }
+
+    /// A changed cell invalidates only viewers that can see that far.
+    #[test]
+    fn set_cell() {
+        let mut fov = get_fov(3);
+        fov.set_cell(12, 5, false, false);
+        assert!(!fov.dirty);
+        fov.set_cell(-1, 5, false, false);
+        fov.set_cell(20, 5, false, false);
+        assert!(!fov.dirty);
+        fov.set_cell(7, 5, false, false);
+        assert!(fov.dirty);
+        fov.update(5, 5);
+        assert!(!fov.dirty);
+    }
+
+    /// A clean field of view recomputes only when the viewer moves.
+    #[test]
+    fn update() {
+        let mut fov = get_fov(3);
+        assert!(fov.explored_map[5][5]);
+        assert!(!fov.explored_map[12][5]);
+        fov.update(10, 5);
+        assert_eq!((fov.x, fov.y), (10, 5));
+        assert!(fov.explored_map[12][5]);
+        assert!(fov.explored_map[5][5]);
+    }
+
+}
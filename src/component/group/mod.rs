@@ -0,0 +1,73 @@
+/// The offsets of the ring of places around a pack leader, clockwise from the north.
+const FORMATION_RING: [(i32, i32); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// An entity's membership of a pack.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Group {
+    /// The pack leader.
+    pub leader_id: usize,
+    /// The member's place in the formation; the leader holds slot zero.
+    pub slot: usize,
+}
+
+/// An entity's membership of a pack.
+impl Group {
+
+    /// Constructor.
+    pub fn new(leader_id: usize, slot: usize) -> Group {
+        Group {
+            leader_id,
+            slot,
+        }
+    }
+
+    /// Indicates whether this member leads the pack.
+    pub fn is_leader(&self) -> bool {
+        self.slot == 0
+    }
+
+    /// Where this member should stand relative to its leader.
+    pub fn get_formation_offset(&self) -> (i32, i32) {
+        get_formation_offset(self.slot)
+    }
+
+}
+
+/// Where the member in a slot should stand relative to its leader.
+///
+/// The first eight followers ring the leader; the next eight ring them, and so on.
+pub fn get_formation_offset(slot: usize) -> (i32, i32) {
+    if slot == 0 {
+        return (0, 0);
+    }
+    let (dx, dy) = FORMATION_RING[(slot - 1) % FORMATION_RING.len()];
+    let scale = 1 + ((slot - 1) / FORMATION_RING.len()) as i32;
+    (dx * scale, dy * scale)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Followers ring the leader, farther out as the pack grows.
+    #[test]
+    fn formation_offset() {
+        assert_eq!(get_formation_offset(0), (0, 0));
+        assert_eq!(get_formation_offset(1), (0, -1));
+        assert_eq!(get_formation_offset(8), (-1, -1));
+        assert_eq!(get_formation_offset(9), (0, -2));
+        assert!(Group::new(3, 0).is_leader());
+        assert!(!Group::new(3, 2).is_leader());
+    }
+
+}
@@ -0,0 +1,75 @@
+/// The items carried by an entity.
+#[derive(Clone, Debug)]
+pub struct Inventory {
+    /// The IDs of the items carried.
+    pub items: Vec<usize>,
+    /// The maximum number of items that can be carried.
+    pub capacity: usize,
+    /// The maximum total weight that can be carried.
+    pub max_weight: i32,
+}
+
+/// The items carried by an entity.
+impl Inventory {
+
+    /// Constructor.
+    pub fn new(capacity: usize, max_weight: i32) -> Inventory {
+        trace!("Entering Inventory::new().");
+        Inventory {
+            items: vec![],
+            capacity,
+            max_weight,
+        }
+    }
+
+    /// Indicates whether the specified item is carried.
+    pub fn contains(&self, id: usize) -> bool {
+        self.items.contains(&id)
+    }
+
+    /// Indicates whether an item of the specified weight can be added.
+    ///
+    /// The weight already carried is supplied by the caller, since the
+    /// inventory only knows the IDs of its items.
+    pub fn can_add(&self, carried_weight: i32, weight: i32) -> bool {
+        self.items.len() < self.capacity && carried_weight + weight <= self.max_weight
+    }
+
+    /// Add an item.
+    pub fn add(&mut self, id: usize) {
+        if !self.contains(id) {
+            self.items.push(id);
+        }
+    }
+
+    /// Remove an item; returns whether it was carried.
+    pub fn remove(&mut self, id: usize) -> bool {
+        let length = self.items.len();
+        self.items.retain(|&item_id| item_id != id);
+        self.items.len() != length
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Inventories are limited by both capacity and weight.
+    #[test]
+    fn can_add() {
+        let mut inventory = Inventory::new(2, 10);
+        assert!(inventory.can_add(0, 10));
+        assert!(!inventory.can_add(5, 6));
+        inventory.add(3);
+        inventory.add(3);
+        assert_eq!(1, inventory.items.len());
+        inventory.add(4);
+        assert!(!inventory.can_add(0, 1));
+        assert!(inventory.remove(3));
+        assert!(!inventory.remove(3));
+        assert!(inventory.can_add(0, 1));
+    }
+
+}
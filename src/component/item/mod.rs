@@ -0,0 +1,19 @@
+/// Something that can be picked up and carried.
+#[derive(Clone, Copy, Debug)]
+pub struct Item {
+    /// The weight of this item.
+    pub weight: i32,
+}
+
+/// Something that can be picked up and carried.
+impl Item {
+
+    /// Constructor.
+    pub fn new(weight: i32) -> Item {
+        trace!("Entering Item::new().");
+        Item {
+            weight,
+        }
+    }
+
+}
@@ -1,6 +1,26 @@
 use std::cmp;
+use std::f64::consts::PI;
 use bear_lib_terminal::Color;
 use rand::Rng;
+use crate::math;
+use math::noise;
+
+/// How quickly flickering lights change, in flickers per unit of time.
+const FLICKER_RATE: f64 = 6.0;
+
+/// The amount of fuel below which a light source starts to dim.
+const DIM_FUEL: i32 = 50;
+
+/// The ways in which a light source can vary over time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Animation {
+    /// A steady light.
+    Steady,
+    /// Random flickering, varying the intensity by up to the specified amount.
+    Flicker(i32),
+    /// Regular pulsing with the specified period and amplitude.
+    Pulse(i32, i32),
+}
 
 /// Something that gives off light.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -11,6 +31,12 @@ pub struct LightSource {
     pub radius: i32,
     /// The intensity of the light produced.
     pub intensity: i32,
+    /// How the light varies over time.
+    pub animation: Animation,
+    /// The turns of fuel remaining, if the light consumes fuel.
+    pub fuel: Option<i32>,
+    /// The seed used to vary this light independently of others.
+    pub seed: u32,
 }
 
 /// Something that gives off light.
@@ -23,6 +49,75 @@ impl LightSource {
             color: color,
             radius: radius,
             intensity: intensity,
+            animation: Animation::Steady,
+            fuel: None,
+            seed: 0,
+        }
+    }
+
+    /// Return a modified version of this light source.
+    pub fn with_animation(&self, animation: Animation) -> LightSource {
+        LightSource {
+            animation,
+            ..*self
+        }
+    }
+
+    /// Return a modified version of this light source.
+    pub fn with_fuel(&self, fuel: Option<i32>) -> LightSource {
+        LightSource {
+            fuel,
+            ..*self
+        }
+    }
+
+    /// Return a modified version of this light source.
+    pub fn with_seed(&self, seed: u32) -> LightSource {
+        LightSource {
+            seed,
+            ..*self
+        }
+    }
+
+    /// Consume a turn's worth of fuel; returns whether the light is still lit.
+    pub fn burn(&mut self) -> bool {
+        trace!("Entering LightSource::burn().");
+        if let Some(fuel) = self.fuel.as_mut() {
+            if *fuel > 0 {
+                *fuel -= 1;
+            }
+            return *fuel > 0;
+        }
+        true
+    }
+
+    /// The intensity of this light source at the specified time.
+    pub fn get_intensity(&self, time: f64) -> i32 {
+        use Animation::*;
+        let intensity = match self.animation {
+            Steady => self.intensity,
+            Flicker(amount) => {
+                let variation = noise::noise_1d(self.seed, time * FLICKER_RATE);
+                self.intensity + (variation * amount as f64) as i32
+            },
+            Pulse(period, amplitude) => {
+                let phase = (self.seed % 1000) as f64 / 1000.0;
+                let angle = 2.0 * PI * (time / cmp::max(period, 1) as f64 + phase);
+                self.intensity + (angle.sin() * amplitude as f64) as i32
+            },
+        };
+        let intensity = match self.fuel {
+            Some(fuel) if fuel < DIM_FUEL => intensity * cmp::max(fuel, 0) / DIM_FUEL,
+            _ => intensity,
+        };
+        cmp::max(intensity, 0)
+    }
+
+    /// This light source as it appears at the specified time.
+    pub fn at_time(&self, time: f64) -> LightSource {
+        LightSource {
+            intensity: self.get_intensity(time),
+            ..*self
         }
     }
 
@@ -71,8 +166,8 @@ pub enum Factory {
     Torch,
     /// A patch of phosphorescent moss.
     Moss,
-    /// A completely random light source.
-    Random,
+    /// A brief, brilliant burst of light.
+    Flare,
 }
 
 /// A factory.
@@ -82,21 +177,35 @@ impl Factory {
     pub fn create(self) -> LightSource {
         use Factory::*;
         match self {
-            Candle => LightSource::new(Color::from_rgb(255, 127, 255), 6, 64),
-            Torch => LightSource::new(Color::from_rgb(255, 127, 0), 10, 96),
-            Moss => LightSource::new(Color::from_rgb(173, 223, 173), 5, 32),
-            Random => {
-                let mut rng = rand::thread_rng();
-                LightSource::new(Color::from_rgb(
-                        rng.gen_range(0, 5) * 60,
-                        rng.gen_range(0, 5) * 60,
-                        rng.gen_range(0, 5) * 60,
-                    ),
-                    rng.gen_range(10, 15),
-                    rng.gen_range(128, 255),
-                )
-            }
+            Candle => LightSource::new(Color::from_rgb(255, 127, 255), 6, 64)
+                .with_animation(Animation::Flicker(8))
+                .with_fuel(Some(2000)),
+            Torch => LightSource::new(Color::from_rgb(255, 127, 0), 10, 96)
+                .with_animation(Animation::Flicker(24))
+                .with_fuel(Some(5000)),
+            Moss => LightSource::new(Color::from_rgb(173, 223, 173), 5, 32)
+                .with_animation(Animation::Pulse(8, 12)),
+            Flare => LightSource::new(Color::from_rgb(255, 255, 224), 16, 255)
+                .with_animation(Animation::Flicker(16))
+                .with_fuel(Some(60)),
         }
     }
 
+    /// Creates a light source, seeded from the given generator.
+    pub fn create_with_rng<R: Rng>(self, rng: &mut R) -> LightSource {
+        self.create().with_seed(rng.gen())
+    }
+
+    /// Creates a completely random light source from the given generator.
+    pub fn create_random<R: Rng>(rng: &mut R) -> LightSource {
+        LightSource::new(Color::from_rgb(
+                rng.gen_range(0, 5) * 60,
+                rng.gen_range(0, 5) * 60,
+                rng.gen_range(0, 5) * 60,
+            ),
+            rng.gen_range(10, 15),
+            rng.gen_range(128, 255),
+        ).with_seed(rng.gen())
+    }
+
 }
@@ -1,10 +1,36 @@
 /// Something that gets dispensed time and has an opportunity to act.
 pub mod actor;
+/// Something that can be used up, such as a potion, scroll or food.
+pub mod consumable;
+/// Something that holds items, such as a chest, sack or corpse.
+pub mod container;
+/// The remains of a dead creature.
+pub mod corpse;
+/// An entity's accumulated experience and character level.
+pub mod experience;
+/// Equipment that can be wielded or worn, and the slots that hold it.
+pub mod equipment;
 /// Something representing an entity's ability to see the game world.
 pub mod field_of_view;
+/// An entity's membership of a pack.
+pub mod group;
+/// The items carried by an entity.
+pub mod inventory;
+/// Something that can be picked up and carried.
+pub mod item;
 /// Something that gives off light.
 pub mod light_source;
+/// An entity's will to keep fighting.
+pub mod morale;
+/// The hunger, thirst and fatigue of a living creature.
+pub mod needs;
+/// Something representing an entity's ability to notice, and avoid notice.
+pub mod perception;
 /// Something positioned within the game world.
 pub mod position;
 /// Something that can be rendered on a map.
 pub mod renderable;
+/// The combat statistics of an entity.
+pub mod stats;
+/// The statuses currently affecting an entity.
+pub mod status;
@@ -0,0 +1,97 @@
+/// The percentage of its courage below which an entity breaks and flees.
+const BREAKING_POINT: i32 = 30;
+
+/// The percentage of its courage above which a fleeing entity rallies.
+const RALLYING_POINT: i32 = 70;
+
+/// The morale lost on seeing an ally die.
+pub const ALLY_DEATH_LOSS: i32 = 25;
+
+/// The morale lost each turn for each fearsome enemy in sight.
+pub const FEAR_LOSS: i32 = 5;
+
+/// The morale regained each turn with no enemy in sight.
+pub const RECOVERY: i32 = 5;
+
+/// An entity's will to keep fighting.
+#[derive(Clone, Copy, Debug)]
+pub struct Morale {
+    /// The current morale.
+    pub current: i32,
+    /// The most morale the entity can have.
+    pub courage: i32,
+    /// Indicates that the entity's morale has broken and it is fleeing.
+    pub is_fleeing: bool,
+}
+
+/// An entity's will to keep fighting.
+impl Morale {
+
+    /// Constructor.
+    pub fn new(courage: i32) -> Morale {
+        Morale {
+            current: courage,
+            courage,
+            is_fleeing: false,
+        }
+    }
+
+    /// The current morale as a fraction of courage.
+    pub fn get_fraction(&self) -> f32 {
+        self.current as f32 / self.courage.max(1) as f32
+    }
+
+    /// The morale lost from a wound, in proportion to the health it took.
+    pub fn get_wound_loss(&self, damage: i32, total_hit_points: i32) -> i32 {
+        damage.max(0) * self.courage / total_hit_points.max(1)
+    }
+
+    /// Raise or lower morale by some amount.
+    ///
+    /// Returns `Some(true)` if the entity broke, `Some(false)` if it rallied,
+    /// and `None` if neither happened.
+    pub fn change(&mut self, amount: i32) -> Option<bool> {
+        self.current = (self.current + amount).max(0).min(self.courage);
+        let percentage = self.current * 100 / self.courage.max(1);
+        if !self.is_fleeing && percentage < BREAKING_POINT {
+            self.is_fleeing = true;
+            return Some(true);
+        }
+        if self.is_fleeing && percentage > RALLYING_POINT {
+            self.is_fleeing = false;
+            return Some(false);
+        }
+        None
+    }
+
+}
+
+/// Indicates whether an enemy is big enough to frighten an entity, judging by their hit points.
+pub fn is_fearsome(own_hit_points: i32, enemy_hit_points: i32) -> bool {
+    enemy_hit_points >= own_hit_points * 2
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Morale breaks when low and only rallies once mostly recovered.
+    #[test]
+    fn change() {
+        let mut morale = Morale::new(100);
+        assert_eq!(morale.change(-50), None);
+        assert_eq!(morale.change(-30), Some(true));
+        assert!(morale.is_fleeing);
+        assert_eq!(morale.change(40), None);
+        assert!(morale.is_fleeing);
+        assert_eq!(morale.change(40), Some(false));
+        assert!(!morale.is_fleeing);
+        assert_eq!(morale.change(500), None);
+        assert_eq!(morale.current, 100);
+        assert_eq!(morale.get_wound_loss(5, 10), 50);
+        assert!(is_fearsome(5, 25));
+        assert!(!is_fearsome(15, 25));
+    }
+
+}
@@ -0,0 +1,227 @@
+use rand::Rng;
+
+/// The most that a need can build up to.
+pub const MAXIMUM: i32 = 1000;
+
+/// The level at which a need starts to press.
+const PRESSING: i32 = 400;
+
+/// The level at which a need becomes desperate.
+const DESPERATE: i32 = 750;
+
+/// The fatigue shed each turn spent asleep.
+pub const SLEEP_RECOVERY: i32 = 10;
+
+/// The number of turns between the hit points lost to starvation.
+const STARVATION_INTERVAL: usize = 10;
+
+/// The things a creature needs.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Kind {
+    /// The need to eat.
+    Hunger,
+    /// The need to drink.
+    Thirst,
+    /// The need to sleep.
+    Fatigue,
+}
+
+/// The things a creature needs.
+impl Kind {
+
+    /// How much this need builds up each turn.
+    pub fn get_rate(self) -> i32 {
+        use Kind::*;
+        match self {
+            Hunger => 1,
+            Thirst => 1,
+            Fatigue => 2,
+        }
+    }
+
+    /// An adjective describing an entity at a level of this need, if it is felt at all.
+    pub fn get_adjective(self, level: Level) -> Option<&'static str> {
+        use Kind::*;
+        use Level::*;
+        match (self, level) {
+            (_, Satisfied) => None,
+            (Hunger, Pressing) => Some("hungry"),
+            (Hunger, Desperate) => Some("famished"),
+            (Hunger, Critical) => Some("starving"),
+            (Thirst, Pressing) => Some("thirsty"),
+            (Thirst, Desperate) => Some("parched"),
+            (Thirst, Critical) => Some("dehydrated"),
+            (Fatigue, Pressing) => Some("tired"),
+            (Fatigue, Desperate) => Some("exhausted"),
+            (Fatigue, Critical) => Some("collapsing"),
+        }
+    }
+
+}
+
+/// How badly something is needed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Level {
+    /// Not felt at all.
+    Satisfied,
+    /// Felt, and worth seeing to.
+    Pressing,
+    /// Hard to think about anything else.
+    Desperate,
+    /// Doing harm.
+    Critical,
+}
+
+/// How badly something is needed.
+impl Level {
+
+    /// The level of a need that has built up to the specified value.
+    pub fn from_value(value: i32) -> Level {
+        if value >= MAXIMUM {
+            Level::Critical
+        } else if value >= DESPERATE {
+            Level::Desperate
+        } else if value >= PRESSING {
+            Level::Pressing
+        } else {
+            Level::Satisfied
+        }
+    }
+
+}
+
+/// A single need and how far it has built up.
+#[derive(Clone, Copy, Debug)]
+pub struct Need {
+    /// The kind of need.
+    pub kind: Kind,
+    /// How far it has built up, from zero to the maximum.
+    pub current: i32,
+}
+
+/// The needs of a living creature, which build up over time.
+#[derive(Clone, Debug, Default)]
+pub struct Needs {
+    /// The needs felt.
+    pub entries: Vec<Need>,
+}
+
+/// The needs of a living creature, which build up over time.
+impl Needs {
+
+    /// Constructor.
+    pub fn new(kinds: &[Kind]) -> Needs {
+        trace!("Entering Needs::new().");
+        Needs {
+            entries: kinds
+                .iter()
+                .map(|&kind| Need {
+                    kind,
+                    current: 0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Start each need somewhere short of pressing, so that creatures don't all tire at once.
+    pub fn scatter<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for need in self.entries.iter_mut() {
+            need.current = rng.gen_range(0, PRESSING);
+        }
+    }
+
+    /// How far the specified need has built up, if it is felt at all.
+    pub fn get(&self, kind: Kind) -> Option<i32> {
+        self.entries
+            .iter()
+            .find(|need| need.kind == kind)
+            .map(|need| need.current)
+    }
+
+    /// How far the specified need has built up, as a fraction of the maximum.
+    pub fn get_fraction(&self, kind: Kind) -> f32 {
+        self.get(kind).map_or(0.0, |current| current as f32 / MAXIMUM as f32)
+    }
+
+    /// How badly the specified need is felt.
+    pub fn get_level(&self, kind: Kind) -> Level {
+        self.get(kind).map_or(Level::Satisfied, Level::from_value)
+    }
+
+    /// Indicates whether a lack of food or drink is doing harm.
+    pub fn is_starving(&self) -> bool {
+        self.get_level(Kind::Hunger) == Level::Critical || self.get_level(Kind::Thirst) == Level::Critical
+    }
+
+    /// The hit points lost to starvation this turn.
+    pub fn get_starvation_damage(&self, turns: usize) -> i32 {
+        if self.is_starving() && turns.is_multiple_of(STARVATION_INTERVAL) {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// The number of turns of sleep needed to shed all fatigue.
+    pub fn get_sleep_duration(&self) -> i32 {
+        (self.get(Kind::Fatigue).unwrap_or(0) / SLEEP_RECOVERY).max(1)
+    }
+
+    /// Ease the specified need by some amount.
+    pub fn satisfy(&mut self, kind: Kind, amount: i32) {
+        for need in self.entries.iter_mut().filter(|need| need.kind == kind) {
+            need.current = (need.current - amount).max(0);
+        }
+    }
+
+    /// Advance a turn; returns each need that has risen to a worse level.
+    ///
+    /// Sleep sheds fatigue, and slows hunger and thirst.
+    pub fn tick(&mut self, is_asleep: bool) -> Vec<(Kind, Level)> {
+        let mut risen = vec![];
+        for need in self.entries.iter_mut() {
+            let level = Level::from_value(need.current);
+            let change = match (need.kind, is_asleep) {
+                (Kind::Fatigue, true) => -SLEEP_RECOVERY,
+                (_, true) => need.kind.get_rate() / 2,
+                (_, false) => need.kind.get_rate(),
+            };
+            need.current = (need.current + change).clamp(0, MAXIMUM);
+            let new_level = Level::from_value(need.current);
+            if new_level > level {
+                risen.push((need.kind, new_level));
+            }
+        }
+        risen
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Needs build up while awake, fatigue falls while asleep, and starvation harms.
+    #[test]
+    fn tick() {
+        let mut needs = Needs::new(&[Kind::Hunger, Kind::Fatigue]);
+        assert_eq!(needs.get(Kind::Thirst), None);
+        assert_eq!(needs.get_level(Kind::Thirst), Level::Satisfied);
+        needs.entries[0].current = PRESSING - 1;
+        assert_eq!(needs.tick(false), vec![(Kind::Hunger, Level::Pressing)]);
+        assert_eq!(needs.get(Kind::Fatigue), Some(2));
+        assert!(needs.tick(true).is_empty());
+        assert_eq!(needs.get(Kind::Fatigue), Some(0));
+        needs.satisfy(Kind::Hunger, 5000);
+        assert_eq!(needs.get(Kind::Hunger), Some(0));
+        assert!(!needs.is_starving());
+        needs.entries[0].current = MAXIMUM;
+        assert!(needs.is_starving());
+        assert_eq!(needs.get_starvation_damage(20), 1);
+        assert_eq!(needs.get_starvation_damage(21), 0);
+        needs.entries[1].current = 300;
+        assert_eq!(needs.get_sleep_duration(), 30);
+    }
+
+}
@@ -0,0 +1,85 @@
+/// The light level at which an entity with no darkvision can see something.
+pub const VISIBILITY_THRESHOLD: i32 = 24;
+
+/// The stealth bonus given to an entity that is sneaking.
+pub const SNEAK_STEALTH_BONUS: i32 = 24;
+
+/// Something representing an entity's ability to notice, and avoid notice.
+#[derive(Clone, Copy, Debug)]
+pub struct Perception {
+    /// Light level that this entity perceives in addition to the ambient light.
+    pub darkvision: i32,
+    /// Light level subtracted from this entity's tile when others look at it.
+    pub stealth: i32,
+    /// Whether this entity is currently sneaking.
+    pub is_sneaking: bool,
+}
+
+/// Something representing an entity's ability to notice, and avoid notice.
+impl Perception {
+
+    /// Constructor.
+    pub fn new(darkvision: i32, stealth: i32) -> Perception {
+        trace!("Entering Perception::new().");
+        Perception {
+            darkvision,
+            stealth,
+            is_sneaking: false,
+        }
+    }
+
+    /// The effective stealth of this entity.
+    pub fn get_stealth(&self) -> i32 {
+        if self.is_sneaking {
+            self.stealth + SNEAK_STEALTH_BONUS
+        } else {
+            self.stealth
+        }
+    }
+
+}
+
+/// Indicates whether something at the given light level can be seen.
+pub fn is_visible(light: i32, observer: Option<Perception>, target: Option<Perception>) -> bool {
+    let darkvision = observer.map_or(0, |perception| perception.darkvision);
+    let stealth = target.map_or(0, |perception| perception.get_stealth());
+    light + darkvision - stealth >= VISIBILITY_THRESHOLD
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Something is seen at the threshold, and not a point below it.
+    #[test]
+    fn threshold() {
+        assert!(is_visible(VISIBILITY_THRESHOLD, None, None));
+        assert!(!is_visible(VISIBILITY_THRESHOLD - 1, None, None));
+        let plain = Some(Perception::new(0, 0));
+        assert!(is_visible(VISIBILITY_THRESHOLD, plain, plain));
+        assert!(!is_visible(VISIBILITY_THRESHOLD - 1, plain, plain));
+    }
+
+    /// Darkvision makes up for missing light, point for point.
+    #[test]
+    fn darkvision() {
+        let observer = Some(Perception::new(10, 0));
+        assert!(is_visible(VISIBILITY_THRESHOLD - 10, observer, None));
+        assert!(!is_visible(VISIBILITY_THRESHOLD - 11, observer, None));
+    }
+
+    /// Stealth takes away from the light, and sneaking adds to stealth.
+    #[test]
+    fn stealth() {
+        let mut target = Perception::new(0, 5);
+        assert!(is_visible(VISIBILITY_THRESHOLD + 5, None, Some(target)));
+        assert!(!is_visible(VISIBILITY_THRESHOLD + 4, None, Some(target)));
+        target.is_sneaking = true;
+        assert_eq!(target.get_stealth(), 5 + SNEAK_STEALTH_BONUS);
+        assert!(!is_visible(VISIBILITY_THRESHOLD + 5, None, Some(target)));
+        assert!(is_visible(VISIBILITY_THRESHOLD + 5 + SNEAK_STEALTH_BONUS, None, Some(target)));
+        assert!(!is_visible(VISIBILITY_THRESHOLD + 4 + SNEAK_STEALTH_BONUS, None, Some(target)));
+    }
+
+}
@@ -120,15 +120,15 @@ impl Position {
         let x_diff = position.x - self.x;
         let y_diff = position.y - self.y;
         match (x_diff, y_diff) {
-            (xd, yd) if xd > 0 && yd > 0 => Some(CompassDirection::South),
+            (xd, yd) if xd > 0 && yd > 0 => Some(CompassDirection::Southeast),
             (xd, yd) if xd > 0 && yd == 0 => Some(CompassDirection::East),
-            (xd, yd) if xd > 0 && yd < 0 => Some(CompassDirection::North),
+            (xd, yd) if xd > 0 && yd < 0 => Some(CompassDirection::Northeast),
             (xd, yd) if xd == 0 && yd > 0 => Some(CompassDirection::South),
             (xd, yd) if xd == 0 && yd == 0 => None,
             (xd, yd) if xd == 0 && yd < 0 => Some(CompassDirection::North),
-            (xd, yd) if xd < 0 && yd > 0 => Some(CompassDirection::South),
+            (xd, yd) if xd < 0 && yd > 0 => Some(CompassDirection::Southwest),
             (xd, yd) if xd < 0 && yd == 0 => Some(CompassDirection::West),
-            (xd, yd) if xd < 0 && yd < 0 => Some(CompassDirection::North),
+            (xd, yd) if xd < 0 && yd < 0 => Some(CompassDirection::Northwest),
             _ => None,
         }
     }
@@ -163,3 +163,34 @@ impl Default for Position {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use command::COMPASS_DIRECTIONS;
+
+    /// Each neighbor lies in its own direction, as does anything farther along that way.
+    #[test]
+    fn direction_to() {
+        let origin = Position::new(Position::default().w, 5, 5, 0);
+        assert_eq!(origin.direction_to(&origin), None);
+        for &compass_direction in COMPASS_DIRECTIONS.iter() {
+            assert_eq!(origin.direction_to(&origin.to_direction(compass_direction)), Some(compass_direction));
+        }
+        let cases = [
+            ((5, 1), CompassDirection::North),
+            ((8, 2), CompassDirection::Northeast),
+            ((9, 5), CompassDirection::East),
+            ((6, 9), CompassDirection::Southeast),
+            ((5, 7), CompassDirection::South),
+            ((0, 9), CompassDirection::Southwest),
+            ((2, 5), CompassDirection::West),
+            ((4, 0), CompassDirection::Northwest),
+        ];
+        for &((x, y), compass_direction) in cases.iter() {
+            assert_eq!(origin.direction_to(&Position::new(origin.w, x, y, 0)), Some(compass_direction));
+        }
+    }
+
+}
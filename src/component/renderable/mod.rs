@@ -84,10 +84,34 @@ pub enum Factory {
     Moss,
     /// A human,
     Human,
+    /// A rock.
+    Rock,
+    /// A dagger.
+    Dagger,
+    /// A bone.
+    Bone,
+    /// A torch.
+    Torch,
+    /// Leather armor.
+    LeatherArmor,
+    /// A helmet.
+    Helmet,
+    /// A potion.
+    Potion,
+    /// A scroll.
+    Scroll,
+    /// Something to eat.
+    Food,
+    /// A chest.
+    Chest,
+    /// A sack.
+    Sack,
     /// A floor (dark).
     Floor,
     /// A wall (dark).
     Wall,
+    /// A puddle of water (dark).
+    Puddle,
 }
 
 impl Factory {
@@ -142,6 +166,61 @@ impl Factory {
                 foreground_color: Some(Color::from_rgb(115, 115, 255)),
                 background_color: None,
             },
+            Rock => Renderable {
+                char: Some('*'),
+                foreground_color: Some(Color::from_rgb(128, 128, 128)),
+                background_color: None,
+            },
+            Dagger => Renderable {
+                char: Some(')'),
+                foreground_color: Some(Color::from_rgb(192, 192, 208)),
+                background_color: None,
+            },
+            Bone => Renderable {
+                char: Some('/'),
+                foreground_color: Some(Color::from_rgb(227, 218, 201)),
+                background_color: None,
+            },
+            Torch => Renderable {
+                char: Some('('),
+                foreground_color: Some(Color::from_rgb(255, 160, 64)),
+                background_color: None,
+            },
+            LeatherArmor => Renderable {
+                char: Some('['),
+                foreground_color: Some(Color::from_rgb(160, 96, 48)),
+                background_color: None,
+            },
+            Helmet => Renderable {
+                char: Some('['),
+                foreground_color: Some(Color::from_rgb(192, 192, 208)),
+                background_color: None,
+            },
+            Potion => Renderable {
+                char: Some('!'),
+                foreground_color: Some(Color::from_rgb(192, 64, 255)),
+                background_color: None,
+            },
+            Scroll => Renderable {
+                char: Some('?'),
+                foreground_color: Some(Color::from_rgb(240, 230, 192)),
+                background_color: None,
+            },
+            Food => Renderable {
+                char: Some(','),
+                foreground_color: Some(Color::from_rgb(192, 128, 64)),
+                background_color: None,
+            },
+            Chest => Renderable {
+                char: Some('='),
+                foreground_color: Some(Color::from_rgb(160, 112, 48)),
+                background_color: None,
+            },
+            Sack => Renderable {
+                char: Some('&'),
+                foreground_color: Some(Color::from_rgb(192, 160, 112)),
+                background_color: None,
+            },
             Floor => Renderable {
                 char: Some('.'),
                 foreground_color: Some(Color::from_rgb(0, 0, 0)),
@@ -152,6 +231,11 @@ impl Factory {
                 foreground_color: Some(Color::from_rgb(0, 0, 0)),
                 background_color: Some(Color::from_rgb(16, 16, 16)),
             },
+            Puddle => Renderable {
+                char: Some('~'),
+                foreground_color: Some(Color::from_rgb(64, 96, 160)),
+                background_color: Some(Color::from_rgb(24, 32, 48)),
+            },
         }
     }
 
@@ -0,0 +1,56 @@
+use crate::combat;
+use combat::dice::Dice;
+
+/// The combat statistics of an entity.
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    /// Bonus to hit when attacking.
+    pub attack: i32,
+    /// Penalty to others' attempts to hit this entity.
+    pub defense: i32,
+    /// Damage absorbed from every hit.
+    pub armor: i32,
+    /// The damage dealt by an unarmed hit.
+    pub damage: Dice,
+    /// The range of this entity's ranged attack, or zero if it has none.
+    pub range: i32,
+    /// The damage dealt by a ranged hit.
+    pub ranged_damage: Dice,
+}
+
+/// The combat statistics of an entity.
+impl Stats {
+
+    /// Constructor.
+    pub fn new(attack: i32, defense: i32, armor: i32, damage: Dice) -> Stats {
+        trace!("Entering Stats::new().");
+        Stats {
+            attack,
+            defense,
+            armor,
+            damage,
+            range: 0,
+            ranged_damage: Dice::new(0, 0, 0),
+        }
+    }
+
+    /// Return a modified version of these stats.
+    pub fn with_ranged_attack(&self, range: i32, damage: Dice) -> Stats {
+        Stats {
+            range,
+            ranged_damage: damage,
+            ..*self
+        }
+    }
+
+}
+
+/// Creates a default instance.
+impl Default for Stats {
+
+    /// Creates a default instance.
+    fn default() -> Self {
+        Stats::new(0, 0, 0, Dice::new(1, 2, 0))
+    }
+
+}
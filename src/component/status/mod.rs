@@ -0,0 +1,162 @@
+/// The kinds of status that can afflict (or bless) an entity.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// Loses a hit point every turn.
+    Poisoned,
+    /// Loses two hit points every turn.
+    Burning,
+    /// Cannot act.
+    Stunned,
+    /// Acts at half speed.
+    Slowed,
+    /// Acts at one-and-a-half times speed.
+    Hasted,
+    /// Sometimes walks in the wrong direction.
+    Confused,
+    /// Cannot act until the duration expires or it is hurt.
+    Asleep,
+}
+
+/// The kinds of status that can afflict (or bless) an entity.
+impl Kind {
+
+    /// The damage that this status deals every turn.
+    pub fn get_damage(self) -> i32 {
+        use Kind::*;
+        match self {
+            Poisoned => 1,
+            Burning => 2,
+            _ => 0,
+        }
+    }
+
+    /// An adjective describing an entity with this status.
+    pub fn get_adjective(self) -> &'static str {
+        use Kind::*;
+        match self {
+            Poisoned => "poisoned",
+            Burning => "burning",
+            Stunned => "stunned",
+            Slowed => "slowed",
+            Hasted => "hasted",
+            Confused => "confused",
+            Asleep => "asleep",
+        }
+    }
+
+}
+
+/// A single status and the turns remaining until it expires.
+#[derive(Clone, Copy, Debug)]
+pub struct Status {
+    /// The kind of status.
+    pub kind: Kind,
+    /// The number of turns remaining.
+    pub duration: i32,
+}
+
+/// The statuses currently affecting an entity.
+#[derive(Clone, Debug, Default)]
+pub struct Statuses {
+    /// The active statuses.
+    pub entries: Vec<Status>,
+}
+
+/// The statuses currently affecting an entity.
+impl Statuses {
+
+    /// Constructor.
+    pub fn new() -> Statuses {
+        trace!("Entering Statuses::new().");
+        Statuses {
+            entries: vec![],
+        }
+    }
+
+    /// Indicates whether a status of the specified kind is active.
+    pub fn has(&self, kind: Kind) -> bool {
+        self.entries.iter().any(|status| status.kind == kind)
+    }
+
+    /// Add a status; an existing status of the same kind lasts whichever is longer.
+    pub fn add(&mut self, kind: Kind, duration: i32) {
+        if let Some(status) = self.entries.iter_mut().find(|status| status.kind == kind) {
+            status.duration = status.duration.max(duration);
+            return;
+        }
+        self.entries.push(Status {
+            kind,
+            duration,
+        });
+    }
+
+    /// Remove any status of the specified kind.
+    pub fn remove(&mut self, kind: Kind) {
+        self.entries.retain(|status| status.kind != kind);
+    }
+
+    /// Indicates whether these statuses prevent the entity from acting.
+    pub fn is_incapacitated(&self) -> bool {
+        self.has(Kind::Stunned) || self.has(Kind::Asleep)
+    }
+
+    /// Modify a speed according to these statuses.
+    pub fn modify_speed(&self, speed: i32) -> i32 {
+        let mut result = speed;
+        if self.has(Kind::Slowed) {
+            result /= 2;
+        }
+        if self.has(Kind::Hasted) {
+            result = result * 3 / 2;
+        }
+        result
+    }
+
+    /// Advance a turn; returns the damage dealt and the kinds that expired.
+    pub fn tick(&mut self) -> (i32, Vec<Kind>) {
+        let mut damage = 0;
+        for status in self.entries.iter_mut() {
+            damage += status.kind.get_damage();
+            status.duration -= 1;
+        }
+        let expired = self.entries
+            .iter()
+            .filter(|status| status.duration <= 0)
+            .map(|status| status.kind)
+            .collect();
+        self.entries.retain(|status| status.duration > 0);
+        (damage, expired)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Statuses deal damage every turn, then expire.
+    #[test]
+    fn tick() {
+        let mut statuses = Statuses::new();
+        statuses.add(Kind::Poisoned, 2);
+        statuses.add(Kind::Burning, 1);
+        assert_eq!((3, vec![Kind::Burning]), statuses.tick());
+        assert_eq!((1, vec![Kind::Poisoned]), statuses.tick());
+        assert!(statuses.entries.is_empty());
+    }
+
+    /// Adding a status again extends, rather than stacks, it.
+    #[test]
+    fn add() {
+        let mut statuses = Statuses::new();
+        statuses.add(Kind::Slowed, 5);
+        statuses.add(Kind::Slowed, 3);
+        assert_eq!(1, statuses.entries.len());
+        assert_eq!(5, statuses.entries[0].duration);
+        assert_eq!(6, statuses.modify_speed(12));
+        statuses.add(Kind::Hasted, 3);
+        assert_eq!(9, statuses.modify_speed(12));
+    }
+
+}
@@ -1,9 +1,53 @@
+use bear_lib_terminal::Color;
+use rand::Rng;
+use crate::body;
+use body::{PartKind, Wound};
 use crate::component;
+use component::consumable::Consumable;
+use component::container::{Container, Kind as ContainerKind};
+use component::corpse::{Corpse, Stage as CorpseStage};
+use component::equipment::{Equipment, Equipped};
+use component::experience::Growth;
+use component::inventory::Inventory;
+use component::light_source::Factory as LightSourceFactory;
+use component::morale::ALLY_DEATH_LOSS;
+use component::needs::Kind as NeedKind;
 use component::position::Position;
+use component::status::{Kind as StatusKind, Statuses};
+use crate::command;
+use command::COMPASS_DIRECTIONS;
+use crate::entity;
+use entity::Entity;
+use crate::event;
+use event::Event;
+use crate::faction;
+use faction::{Consequence, Faction, Penalty, Standing as FactionStanding};
 use crate::game;
 use game::Game;
+use crate::item;
+use item::Factory as ItemFactory;
+use crate::loot;
+use loot::Source as LootSource;
+use crate::pack;
 use crate::species;
-use species::Factory as SpeciesFactory;
+use species::{Factory as SpeciesFactory, Species};
+use crate::tile;
+use tile::Tile;
+
+/// The capacity of a corpse for the items its creature carried and dropped.
+const CORPSE_CAPACITY: usize = 26;
+
+/// The weight a corpse can hold.
+const CORPSE_MAX_WEIGHT: i32 = 1000;
+
+/// The number of random positions tried when teleporting.
+const TELEPORT_ATTEMPTS: usize = 100;
+
+/// The hunger or thirst eased by each point of nourishment or refreshment.
+const SATIATION_PER_POINT: i32 = 100;
+
+/// The nourishment, and refreshment, in a mouthful of moss.
+const MOSS_NOURISHMENT: i32 = 2;
 
 /// A direct modification of the game world.
 #[derive(Clone, Debug)]
@@ -12,6 +56,52 @@ pub enum Effect {
     MoveEntity(Position, Position),
     /// Damage the entity by some amount.
     DamageEntityBody(usize, i32),
+    /// Damage a part of the entity's body by some amount.
+    WoundEntityBodyPart(usize, usize, i32),
+    /// Heal the entity by some amount.
+    HealEntityBody(usize, i32),
+    /// The entity rests undisturbed, regenerating faster this turn.
+    Rest,
+    /// Afflict the entity with a status for some number of turns.
+    InflictStatus(usize, StatusKind, i32),
+    /// The entity gains experience points.
+    GainExperience(i32),
+    /// The entity grows stronger, spending a level gained.
+    Grow(Growth),
+    /// The corpse decays for a turn, and eventually rots into new growth.
+    DecayCorpse,
+    /// Eat the specified corpse.
+    EatCorpse(usize),
+    /// Graze on the specified moss.
+    EatMoss(usize),
+    /// The entity is nourished by some amount, easing its hunger and mending its body.
+    Nourish(i32),
+    /// The entity's thirst is slaked by some amount.
+    Quench(i32),
+    /// Pick up the specified item from the ground.
+    PickUpItem(usize),
+    /// Drop the specified item onto the ground.
+    DropItem(usize),
+    /// Open (or search) the specified container.
+    OpenContainer(usize),
+    /// Take the specified item from a container.
+    TakeItem(usize, usize),
+    /// Put the specified carried item into a container.
+    StowItem(usize, usize),
+    /// Wield or wear the specified carried item.
+    EquipItem(usize),
+    /// Stop wielding or wearing the specified item.
+    UnequipItem(usize),
+    /// Consume the specified carried item, applying its use effect.
+    ConsumeItem(usize),
+    /// Move the entity to a random open position.
+    TeleportEntity,
+    /// Reveal the layout of the map to the entity.
+    RevealMap,
+    /// Light up the area around the entity for a while.
+    LightBurst,
+    /// Grow moss all around the entity.
+    SummonMoss,
     /// Remove the entity entirely.
     RemoveEntity,
     /// Kill the entity completely.
@@ -20,8 +110,22 @@ pub enum Effect {
     UpdateEntityFov,
     /// Create a new entity at this position.
     CreateEntity(Position, SpeciesFactory),
+    /// Create a new item lying at this position.
+    CreateItem(Position, ItemFactory),
     /// Change species of the specified entity.
     ChangeEntitySpecies(SpeciesFactory),
+    /// The entity's light source goes out.
+    ExtinguishLightSource,
+    /// Start or stop the entity sneaking.
+    SetSneaking(bool),
+    /// Replace the tile at this position (e.g. digging, opening a door).
+    ChangeTile(Position, Tile),
+    /// A faction punishes (or pardons) the entity for its conduct.
+    ApplyConsequence(Faction, Consequence),
+    /// The entity's morale rises or falls by some amount.
+    ChangeMorale(i32),
+    /// The entity calls on its pack for help against the specified threat.
+    CallForHelp(usize),
 }
 
 /// A direct modification of the game world.
@@ -32,44 +136,491 @@ impl Effect {
         use Effect::*;
         match self {
             MoveEntity(position1, position2) => {
-                let mut entity = &mut game.entities[id];
+                let entity = &mut game.entities[id];
                 debug!("Moving entity {} from ({}, {}) to ({}, {}).", entity.name, position1.x, position1.y, position2.x, position2.y);
                 game.map.move_entity(entity.id, position1.x as usize, position1.y as usize, position2.x as usize, position2.y as usize);
                 entity.position = Some(*position2);
+                if entity.blocks_light {
+                    game.notify_map_changed(position1.x as usize, position1.y as usize);
+                    game.notify_map_changed(position2.x as usize, position2.y as usize);
+                }
                 UpdateEntityFov.execute(id, game);
             },
             DamageEntityBody(target_id, hp) => {
-                println!("Entering DamageEntityBody() for id {}.", target_id);
+                trace!("Entering DamageEntityBody() for id {}.", target_id);
                 let entity = &mut game.entities[*target_id];
+                if let Some(statuses) = entity.statuses.as_mut() {
+                    statuses.remove(StatusKind::Asleep);
+                }
                 if let Some(body) = entity.body.as_mut() {
-                    println!("Damaging entity {} ({} -> {}).", entity.name, body.current_hit_points, body.current_hit_points - hp);
-                    body.current_hit_points -= hp;
-                    if body.current_hit_points <= 0 {
-                        KillEntity.execute(*target_id, game);
+                    debug!("Damaging entity {} ({} -> {}).", entity.name, body.current_hit_points, body.current_hit_points - hp);
+                    match body.damage(*hp) {
+                        Some(Wound::Fatal) => kill(id, *target_id, game),
+                        _ => lose_nerve(*target_id, *hp, game),
                     }
                 }
-                println!("Exiting DamageEntityBody() for id {}.", target_id);
+                trace!("Exiting DamageEntityBody() for id {}.", target_id);
             },
-            KillEntity => {
-                println!("Entering KillEntity() for id {}.", id);
+            WoundEntityBodyPart(target_id, part, hp) => {
+                trace!("Entering WoundEntityBodyPart() for id {}.", target_id);
+                let entity = &mut game.entities[*target_id];
+                if let Some(statuses) = entity.statuses.as_mut() {
+                    statuses.remove(StatusKind::Asleep);
+                }
+                let wound = entity.body.as_mut().and_then(|body| body.wound(*part, *hp));
+                match wound {
+                    Some(Wound::Fatal) => kill(id, *target_id, game),
+                    Some(Wound::Destroyed(PartKind::Eye)) => {
+                        if let Some(fov) = entity.field_of_view.as_mut() {
+                            fov.base_radius = (fov.base_radius / 2).max(1);
+                        }
+                        update_fov_radius(*target_id, game);
+                    },
+                    _ => {},
+                }
+                if wound != Some(Wound::Fatal) {
+                    lose_nerve(*target_id, *hp, game);
+                }
+                trace!("Exiting WoundEntityBodyPart() for id {}.", target_id);
+            },
+            HealEntityBody(target_id, hp) => {
+                trace!("Entering HealEntityBody() for id {}.", target_id);
+                let entity = &mut game.entities[*target_id];
+                if let Some(body) = entity.body.as_mut() {
+                    debug!("Healing entity {} ({} -> {}).", entity.name, body.current_hit_points, (body.current_hit_points + hp).min(body.total_hit_points));
+                    body.heal(*hp);
+                }
+                if id != *target_id {
+                    Event::Assist(id, *target_id).publish(game);
+                }
+            },
+            Rest => {
+                if let Some(body) = game.entities[id].body.as_mut() {
+                    body.is_resting = true;
+                }
+            },
+            InflictStatus(target_id, kind, duration) => {
+                trace!("Entering InflictStatus({:?}, {}) for id {}.", kind, duration, target_id);
+                let entity = &mut game.entities[*target_id];
+                if entity.body.is_none() {
+                    return;
+                }
+                entity.statuses
+                    .get_or_insert_with(Statuses::new)
+                    .add(*kind, *duration);
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, *target_id) {
+                    let message = format!("{} is {}!", game.entities[*target_id].name, kind.get_adjective());
+                    game.add_message(message, Color::from_rgb(224, 160, 255));
+                }
+            },
+            GainExperience(points) => {
+                trace!("Entering GainExperience({}) for id {}.", points, id);
+                let gained = match game.entities[id].experience.as_mut() {
+                    Some(experience) => experience.gain(*points),
+                    None => return,
+                };
+                if gained == 0 {
+                    return;
+                }
+                let level = game.entities[id].experience.map_or(1, |experience| experience.level);
+                if id == game.player_id {
+                    game.add_message(format!("You reach level {}!", level), Color::from_rgb(255, 224, 64));
+                } else {
+                    for offset in 0..gained {
+                        Grow(Growth::for_level(level - gained + 1 + offset)).execute(id, game);
+                    }
+                }
+            },
+            Grow(growth) => {
+                trace!("Entering Grow({:?}) for id {}.", growth, id);
+                game.entities[id].grow(*growth);
+            },
+            DecayCorpse => {
                 let entity = &mut game.entities[id];
-                println!("Killing entity {}!", entity.name);
-                entity.species = None;
-                entity.body = None;
-                entity.actor = None;
-                entity.agent = None;
-                entity.field_of_view = None;
-                entity.light_source = None;
+                let (stage, name) = match entity.corpse.as_mut() {
+                    Some(corpse) => match corpse.decay() {
+                        Some(stage) => (stage, corpse.name.clone()),
+                        None => return,
+                    },
+                    None => return,
+                };
+                debug!("Corpse {} decays to {:?}.", entity.name, stage);
+                if stage == CorpseStage::Rotted {
+                    let position = entity.position;
+                    RemoveEntity.execute(id, game);
+                    if let Some(position) = position {
+                        let species_factory = if game.rng.gen_range(0, 10) < 7 {
+                            SpeciesFactory::Mushroom
+                        } else {
+                            SpeciesFactory::Moss
+                        };
+                        CreateEntity(position, species_factory).execute(id, game);
+                    }
+                    return;
+                }
+                entity.name = stage.get_name(&name);
                 if let Some(renderable) = entity.renderable.as_mut() {
-                    renderable.char = Some('%');
-                    renderable.background_color = None;
+                    renderable.foreground_color = Some(stage.get_color());
+                }
+            },
+            EatCorpse(corpse_id) => {
+                trace!("Entering EatCorpse({}) for id {}.", corpse_id, id);
+                let stage = match &game.entities[*corpse_id].corpse {
+                    Some(corpse) if corpse.is_edible() => corpse.get_stage(),
+                    _ => return,
+                };
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, id) {
+                    let message = format!("{} eats the {}.", game.entities[id].name, game.entities[*corpse_id].name);
+                    game.add_message(message, Color::from_rgb(192, 160, 128));
+                }
+                RemoveEntity.execute(*corpse_id, game);
+                Nourish(stage.get_nutrition()).execute(id, game);
+                // There's still blood in it.
+                Quench(stage.get_nutrition()).execute(id, game);
+                if stage.is_tainted() {
+                    InflictStatus(id, StatusKind::Poisoned, 5).execute(id, game);
+                }
+            },
+            EatMoss(moss_id) => {
+                trace!("Entering EatMoss({}) for id {}.", moss_id, id);
+                if game.entities[*moss_id].species != Some(Species::Moss) {
+                    return;
+                }
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, id) {
+                    let message = format!("{} grazes on the moss.", game.entities[id].name);
+                    game.add_message(message, Color::from_rgb(173, 223, 173));
+                }
+                RemoveEntity.execute(*moss_id, game);
+                Nourish(MOSS_NOURISHMENT).execute(id, game);
+                Quench(MOSS_NOURISHMENT).execute(id, game);
+            },
+            Nourish(amount) => {
+                trace!("Entering Nourish({}) for id {}.", amount, id);
+                if let Some(needs) = game.entities[id].needs.as_mut() {
+                    needs.satisfy(NeedKind::Hunger, amount * SATIATION_PER_POINT);
+                }
+                HealEntityBody(id, *amount).execute(id, game);
+            },
+            Quench(amount) => {
+                trace!("Entering Quench({}) for id {}.", amount, id);
+                if let Some(needs) = game.entities[id].needs.as_mut() {
+                    needs.satisfy(NeedKind::Thirst, amount * SATIATION_PER_POINT);
+                }
+            },
+            PickUpItem(item_id) => {
+                trace!("Entering PickUpItem({}) for id {}.", item_id, id);
+                let position = match game.entities[*item_id].position {
+                    Some(position) => position,
+                    None => return,
+                };
+                match game.entities[id].inventory.as_mut() {
+                    Some(inventory) => inventory.add(*item_id),
+                    None => return,
+                }
+                game.map.remove_item(*item_id, position.x as usize, position.y as usize);
+                game.entities[*item_id].position = None;
+                let player_id = game.player_id;
+                if id == player_id {
+                    let message = format!("You pick up the {}.", game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} picks up the {}.", game.entities[id].name, game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+            },
+            DropItem(item_id) => {
+                trace!("Entering DropItem({}) for id {}.", item_id, id);
+                let position = match game.entities[id].position {
+                    Some(position) => position,
+                    None => return,
+                };
+                let is_removed = game.entities[id].inventory
+                    .as_mut()
+                    .is_some_and(|inventory| inventory.remove(*item_id));
+                if !is_removed {
+                    return;
+                }
+                take_off(id, *item_id, game);
+                put_down(*item_id, position, game);
+                let player_id = game.player_id;
+                if id == player_id {
+                    let message = format!("You drop the {}.", game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} drops the {}.", game.entities[id].name, game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+            },
+            OpenContainer(container_id) => {
+                trace!("Entering OpenContainer({}) for id {}.", container_id, id);
+                let kind = match game.entities[*container_id].container {
+                    Some(container) => container.kind,
+                    None => return,
+                };
+                let is_empty = game.entities[*container_id].inventory
+                    .as_ref()
+                    .is_none_or(|inventory| inventory.items.is_empty());
+                if id == game.player_id {
+                    let mut message = format!("You {} the {}.", kind.get_verb(), game.entities[*container_id].name);
+                    if is_empty {
+                        message.push_str("  It is empty.");
+                    }
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+            },
+            TakeItem(container_id, item_id) => {
+                trace!("Entering TakeItem({}, {}) for id {}.", container_id, item_id, id);
+                if !transfer(*container_id, id, *item_id, game) {
+                    return;
+                }
+                let player_id = game.player_id;
+                if id == player_id {
+                    let message = format!("You take the {} from the {}.", game.get_item_name(*item_id), game.entities[*container_id].name);
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} takes the {} from the {}.", game.entities[id].name, game.get_item_name(*item_id), game.entities[*container_id].name);
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+            },
+            StowItem(container_id, item_id) => {
+                trace!("Entering StowItem({}, {}) for id {}.", container_id, item_id, id);
+                if !transfer(id, *container_id, *item_id, game) {
+                    return;
+                }
+                let player_id = game.player_id;
+                if id == player_id {
+                    let message = format!("You put the {} into the {}.", game.get_item_name(*item_id), game.entities[*container_id].name);
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} puts the {} into the {}.", game.entities[id].name, game.get_item_name(*item_id), game.entities[*container_id].name);
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
                 }
-                entity.blocks_movement = false;
-                println!("Entering KillEntity() for id {}.", id);
+            },
+            EquipItem(item_id) => {
+                trace!("Entering EquipItem({}) for id {}.", item_id, id);
+                let equippable = match game.entities[*item_id].equippable {
+                    Some(equippable) => equippable,
+                    None => return,
+                };
+                let previous_id = game.entities[id].equipment
+                    .as_ref()
+                    .and_then(|equipment| equipment.get(equippable.slot));
+                if let Some(previous_id) = previous_id {
+                    if previous_id == *item_id {
+                        return;
+                    }
+                    UnequipItem(previous_id).execute(id, game);
+                }
+                let lent_light = if game.entities[id].light_source.is_some() {
+                    None
+                } else {
+                    game.entities[*item_id].light_source.take()
+                };
+                let entity = &mut game.entities[id];
+                let equipment = entity.equipment.get_or_insert_with(Equipment::new);
+                equipment.equip(Equipped {
+                    item_id: *item_id,
+                    equippable,
+                    is_lighting: lent_light.is_some(),
+                });
+                if lent_light.is_some() {
+                    entity.light_source = lent_light;
+                }
+                if equippable.fov_radius != 0 {
+                    update_fov_radius(id, game);
+                }
+                let player_id = game.player_id;
+                if id == player_id {
+                    let message = format!("You {} the {}.", equippable.slot.get_verb(), game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} {}s the {}.", game.entities[id].name, equippable.slot.get_verb(), game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+            },
+            UnequipItem(item_id) => {
+                trace!("Entering UnequipItem({}) for id {}.", item_id, id);
+                if !take_off(id, *item_id, game) {
+                    return;
+                }
+                let player_id = game.player_id;
+                if id == player_id {
+                    let message = format!("You put away the {}.", game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} puts away the {}.", game.entities[id].name, game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+            },
+            ConsumeItem(item_id) => {
+                trace!("Entering ConsumeItem({}) for id {}.", item_id, id);
+                let consumable = match game.entities[*item_id].consumable {
+                    Some(consumable) => consumable,
+                    None => return,
+                };
+                let is_removed = game.entities[id].inventory
+                    .as_mut()
+                    .is_some_and(|inventory| inventory.remove(*item_id));
+                if !is_removed {
+                    return;
+                }
+                let player_id = game.player_id;
+                let verb = consumable.kind.get_verb();
+                if id == player_id {
+                    let message = format!("You {} the {}.", verb, game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                } else if game.can_see_entity(player_id, id) {
+                    let message = format!("{} {}s the {}.", game.entities[id].name, verb, game.get_item_name(*item_id));
+                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                }
+                RemoveEntity.execute(*item_id, game);
+                consumable.use_effect.get_effect(id).execute(id, game);
+                if id == player_id {
+                    identify(*item_id, consumable, game);
+                }
+            },
+            TeleportEntity => {
+                trace!("Entering TeleportEntity() for id {}.", id);
+                let position1 = match game.entities[id].position {
+                    Some(position) => position,
+                    None => return,
+                };
+                for _ in 0..TELEPORT_ATTEMPTS {
+                    let x = game.rng.gen_range(1, game.map.width as i32 - 1);
+                    let y = game.rng.gen_range(1, game.map.height as i32 - 1);
+                    if game.map.get_tile(x as usize, y as usize).blocks_movement {
+                        continue;
+                    }
+                    if game.get_entities(x, y).iter().any(|entity| entity.blocks_movement) {
+                        continue;
+                    }
+                    let player_id = game.player_id;
+                    if id != player_id && game.can_see_entity(player_id, id) {
+                        let message = format!("{} vanishes!", game.entities[id].name);
+                        game.add_message(message, Color::from_rgb(192, 160, 255));
+                    }
+                    MoveEntity(position1, Position::new(position1.w, x, y, position1.z)).execute(id, game);
+                    if id == player_id {
+                        game.add_message("You feel yourself wrenched elsewhere.".to_string(), Color::from_rgb(192, 160, 255));
+                    }
+                    return;
+                }
+            },
+            RevealMap => {
+                trace!("Entering RevealMap() for id {}.", id);
+                let width = game.map.width as i32;
+                let height = game.map.height as i32;
+                let mut revealed = vec![];
+                for y in 0..height {
+                    for x in 0..width {
+                        let is_near_floor = (-1..=1)
+                            .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+                            .filter(|&(x2, y2)| x2 >= 0 && y2 >= 0 && x2 < width && y2 < height)
+                            .any(|(x2, y2)| !game.map.get_tile(x2 as usize, y2 as usize).blocks_movement);
+                        if is_near_floor {
+                            revealed.push((x as usize, y as usize));
+                        }
+                    }
+                }
+                if let Some(fov) = game.entities[id].field_of_view.as_mut() {
+                    for (x, y) in revealed {
+                        fov.explored_map[x][y] = true;
+                    }
+                }
+                if id == game.player_id {
+                    game.add_message("A map coalesces in your mind.".to_string(), Color::from_rgb(192, 160, 255));
+                }
+            },
+            LightBurst => {
+                trace!("Entering LightBurst() for id {}.", id);
+                let position = match game.entities[id].position {
+                    Some(position) => position,
+                    None => return,
+                };
+                let mut flare = Entity::new("Flare".to_string());
+                flare.light_source = Some(LightSourceFactory::Flare.create().with_seed(game.rng.gen()));
+                flare.position = Some(position);
+                let flare_id = game.entities.len();
+                flare.id = flare_id;
+                game.entities.push(flare);
+                game.map.insert_entity(flare_id, position.x as usize, position.y as usize);
+                game.update_light_map();
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, id) {
+                    game.add_message("A brilliant light bursts forth!".to_string(), Color::from_rgb(255, 255, 224));
+                }
+            },
+            SummonMoss => {
+                trace!("Entering SummonMoss() for id {}.", id);
+                let position = match game.entities[id].position {
+                    Some(position) => position,
+                    None => return,
+                };
+                for compass_direction in COMPASS_DIRECTIONS.iter() {
+                    let position2 = position.to_direction(*compass_direction);
+                    if !game.map.is_position_in_bounds(&position2) || game.map.get_tile_at_position(&position2).blocks_movement {
+                        continue;
+                    }
+                    if !game.get_entities(position2.x, position2.y).is_empty() {
+                        continue;
+                    }
+                    CreateEntity(position2, SpeciesFactory::Moss).execute(id, game);
+                }
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, id) {
+                    game.add_message("Moss sprouts all around!".to_string(), Color::from_rgb(173, 223, 173));
+                }
+            },
+            KillEntity => {
+                trace!("Entering KillEntity() for id {}.", id);
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, id) {
+                    let message = if id == player_id {
+                        "You die...".to_string()
+                    } else {
+                        format!("{} dies.", game.entities[id].name)
+                    };
+                    game.add_message(message, Color::from_rgb(255, 64, 64));
+                }
+                match game.entities[id].species {
+                    Some(Species::Mushroom) => {
+                        if let Some(position) = game.entities[id].position {
+                            CreateItem(position, ItemFactory::Mushroom).execute(id, game);
+                        }
+                        RemoveEntity.execute(id, game);
+                        return;
+                    },
+                    Some(Species::Moss) | Some(Species::MossSeed) => {
+                        RemoveEntity.execute(id, game);
+                        return;
+                    },
+                    _ => {},
+                }
+                pack::leave(id, game);
+                let mourner_ids = get_mourner_ids(id, game);
+                strip_equipment(id, game);
+                fill_corpse(id, game);
+                leave_corpse(id, game);
+                for mourner_id in mourner_ids {
+                    ChangeMorale(-ALLY_DEATH_LOSS).execute(mourner_id, game);
+                }
+                trace!("Exiting KillEntity() for id {}.", id);
             },
             RemoveEntity => {
-                println!("Entering RemoveEntity() for id {}.", id);
+                trace!("Entering RemoveEntity() for id {}.", id);
+                if let (Some(inventory), Some(position)) = (game.entities[id].inventory.take(), game.entities[id].position) {
+                    // Whatever the entity held spills onto the ground.
+                    for item_id in inventory.items {
+                        take_off(id, item_id, game);
+                        put_down(item_id, position, game);
+                    }
+                }
                 let entity = &mut game.entities[id];
+                entity.container = None;
+                entity.corpse = None;
                 entity.species = None;
                 entity.body = None;
                 entity.actor = None;
@@ -77,36 +628,333 @@ impl Effect {
                 entity.field_of_view = None;
                 entity.light_source = None;
                 entity.renderable = None;
-                if let Some(position) = &entity.position {
-                    game.map.remove_entity(id, position.x as usize, position.y as usize);
+                entity.morale = None;
+                entity.needs = None;
+                entity.perception = None;
+                entity.stats = None;
+                entity.statuses = None;
+                if let Some(position) = entity.position.take() {
+                    let blocks_light = entity.blocks_light;
+                    entity.blocks_light = false;
+                    if entity.item.is_some() {
+                        game.map.remove_item(id, position.x as usize, position.y as usize);
+                    } else {
+                        game.map.remove_entity(id, position.x as usize, position.y as usize);
+                    }
+                    if blocks_light {
+                        game.notify_map_changed(position.x as usize, position.y as usize);
+                    }
                 }
             },
             UpdateEntityFov => {
-                println!("Entering UpdateEntityFov() for id {}.", id);
+                trace!("Entering UpdateEntityFov() for id {}.", id);
                 let entity = &mut game.entities[id];
                 if let Some(position) = &entity.position {
-                        println!("Updating FoV for {:?}.", entity);
+                        debug!("Updating FoV for {:?}.", entity);
                     if let Some(fov) = &mut entity.field_of_view.as_mut() {
                         fov.update(position.x, position.y);
                     }
                 }
             },
             CreateEntity(position, species_factory) => {
-                println!("Entering CreateEntity({:?}, {:?}) for id {}.", position, species_factory, id);
-                let mut entity = species_factory.create();
+                trace!("Entering CreateEntity({:?}, {:?}) for id {}.", position, species_factory, id);
+                let mut entity = species_factory.create(&mut game.rng);
                 entity.position = Some(*position);
                 let id = game.entities.len();
                 entity.id = id;
+                let blocks_light = entity.blocks_light;
                 game.entities.push(entity);
                 game.map.insert_entity(id, position.x as usize, position.y as usize);
+                if blocks_light {
+                    game.notify_map_changed(position.x as usize, position.y as usize);
+                }
+            },
+            CreateItem(position, item_factory) => {
+                trace!("Entering CreateItem({:?}, {:?}) for id {}.", position, item_factory, id);
+                let mut item = item_factory.create();
+                item.position = Some(*position);
+                let item_id = game.entities.len();
+                item.id = item_id;
+                game.entities.push(item);
+                game.map.insert_item(item_id, position.x as usize, position.y as usize);
             },
             ChangeEntitySpecies(species_factory) => {
-                println!("Entering ChangeEntitySpecies({:?}) for id {}.", species_factory, id);
-                let new_entity = species_factory.create();
+                trace!("Entering ChangeEntitySpecies({:?}) for id {}.", species_factory, id);
+                let new_entity = species_factory.create(&mut game.rng);
                 let old_entity = &mut game.entities[id];
+                let blocked_light = old_entity.blocks_light;
                 old_entity.set(&new_entity);
+                if blocked_light != new_entity.blocks_light {
+                    if let Some(position) = old_entity.position {
+                        game.notify_map_changed(position.x as usize, position.y as usize);
+                    }
+                }
+            },
+            ExtinguishLightSource => {
+                let entity = &mut game.entities[id];
+                debug!("The light of entity {} goes out.", entity.name);
+                entity.light_source = None;
+                // A flare is nothing but its light, so nothing is left once it burns out.
+                if entity.body.is_none() && entity.item.is_none() && entity.container.is_none() && entity.renderable.is_none() {
+                    RemoveEntity.execute(id, game);
+                }
+            },
+            SetSneaking(is_sneaking) => {
+                trace!("Entering SetSneaking({}) for id {}.", is_sneaking, id);
+                if let Some(perception) = game.entities[id].perception.as_mut() {
+                    perception.is_sneaking = *is_sneaking;
+                }
+            },
+            ChangeTile(position, tile) => {
+                trace!("Entering ChangeTile({:?}) for id {}.", position, id);
+                if !game.map.is_position_in_bounds(position) {
+                    return;
+                }
+                game.map.set_tile(position.x as usize, position.y as usize, tile.clone());
+                game.notify_map_changed(position.x as usize, position.y as usize);
+            },
+            ChangeMorale(amount) => {
+                trace!("Entering ChangeMorale({}) for id {}.", amount, id);
+                let change = match game.entities[id].morale.as_mut() {
+                    Some(morale) => morale.change(*amount),
+                    None => return,
+                };
+                let player_id = game.player_id;
+                if let Some(is_broken) = change {
+                    if game.can_see_entity(player_id, id) {
+                        let message = if is_broken {
+                            format!("{} turns to flee!", game.entities[id].name)
+                        } else {
+                            format!("{} rallies.", game.entities[id].name)
+                        };
+                        game.add_message(message, Color::from_rgb(224, 224, 128));
+                    }
+                }
+            },
+            CallForHelp(threat_id) => {
+                trace!("Entering CallForHelp({}) for id {}.", threat_id, id);
+                let player_id = game.player_id;
+                if game.can_see_entity(player_id, id) {
+                    let message = format!("{} calls for help!", game.entities[id].name);
+                    game.add_message(message, Color::from_rgb(224, 224, 128));
+                }
+                Event::CallForHelp(id, *threat_id).publish(game);
+            },
+            ApplyConsequence(faction, consequence) => {
+                trace!("Entering ApplyConsequence({:?}, {:?}) for id {}.", faction, consequence, id);
+                let is_player = id == game.player_id;
+                let entity = &mut game.entities[id];
+                let message = match consequence {
+                    Consequence::ChangeStanding(delta) => {
+                        entity.change_standing(*faction, *delta);
+                        if *delta < 0 {
+                            format!("{} think less of you.", faction.get_name())
+                        } else {
+                            format!("{} think better of you.", faction.get_name())
+                        }
+                    },
+                    Consequence::Penalty(penalty) => {
+                        get_standing_mut(entity, *faction).penalty = Some(*penalty);
+                        format!("{} now consider you {}.", faction.get_name(), penalty.get_name())
+                    },
+                    Consequence::Assault => {
+                        let hit_points = entity.body.as_ref().map_or(0, |body| body.current_hit_points - 1);
+                        if hit_points > 0 {
+                            DamageEntityBody(id, hit_points).execute(id, game);
+                        }
+                        format!("{} beat you within an inch of your life!", faction.get_name())
+                    },
+                    Consequence::Death => {
+                        KillEntity.execute(id, game);
+                        format!("{} put you to death.", faction.get_name())
+                    },
+                    Consequence::Abandoned => {
+                        get_standing_mut(entity, *faction).standing = 0;
+                        format!("{} abandon you.", faction.get_name())
+                    },
+                    Consequence::Unjail | Consequence::Unexile => {
+                        let standing = get_standing_mut(entity, *faction);
+                        match (consequence, standing.penalty) {
+                            (Consequence::Unjail, Some(Penalty::Jail(_))) | (Consequence::Unexile, Some(Penalty::Exile(_))) => standing.penalty = None,
+                            _ => return,
+                        }
+                        format!("{} pardon you.", faction.get_name())
+                    },
+                };
+                if is_player {
+                    game.add_message(message, Color::from_rgb(255, 160, 64));
+                }
             },
         }
     }
 
 }
+
+/// Kill the target, rewarding the killer (if any) with experience.
+fn kill(killer_id: usize, target_id: usize, game: &mut Game) {
+    let experience_value = game.entities[target_id].get_experience_value();
+    Effect::KillEntity.execute(target_id, game);
+    if killer_id != target_id && game.entities[killer_id].body.is_some() {
+        Effect::GainExperience(experience_value).execute(killer_id, game);
+        Event::Kill(killer_id, target_id).publish(game);
+    }
+}
+
+/// The allies that see the entity die, and whose morale suffers for it.
+fn get_mourner_ids(id: usize, game: &Game) -> Vec<usize> {
+    game.entities
+        .iter()
+        .filter(|other| other.id != id && other.morale.is_some() && other.is_ally_of(&game.entities[id]))
+        .filter(|other| game.can_see_entity(other.id, id))
+        .map(|other| other.id)
+        .collect()
+}
+
+/// Take off everything the entity has equipped, so that it falls with the body.
+fn strip_equipment(id: usize, game: &mut Game) {
+    let equipped_ids = game.entities[id].equipment
+        .as_ref()
+        .map_or(vec![], |equipment| equipment.entries.iter().map(|entry| entry.item_id).collect());
+    for item_id in equipped_ids {
+        take_off(id, item_id, game);
+    }
+}
+
+/// Give the dying entity somewhere to keep its belongings, and roll its loot.
+fn fill_corpse(id: usize, game: &mut Game) {
+    let loot = match (game.entities[id].species, game.entities[id].position) {
+        (Some(species), Some(position)) => game.loot_tables.roll(LootSource::Creature(species), position.z, &mut game.rng),
+        _ => vec![],
+    };
+    if game.entities[id].inventory.is_none() {
+        game.entities[id].inventory = Some(Inventory::new(CORPSE_CAPACITY, CORPSE_MAX_WEIGHT));
+    }
+    for item_factory in loot {
+        game.give_item(id, item_factory);
+    }
+}
+
+/// Turn the entity into a corpse, stripping everything that belonged to the living creature.
+fn leave_corpse(id: usize, game: &mut Game) {
+    let player_id = game.player_id;
+    let entity = &mut game.entities[id];
+    debug!("Killing entity {}!", entity.name);
+    entity.container = Some(Container::new(ContainerKind::Corpse));
+    entity.equipment = None;
+    let corpse = Corpse::new(entity.species, entity.name.clone());
+    let stage = corpse.get_stage();
+    entity.name = stage.get_name(&corpse.name);
+    entity.corpse = Some(corpse);
+    entity.species = None;
+    entity.body = None;
+    entity.actor = None;
+    entity.agent = None;
+    if id != player_id {
+        // The player's field of view survives, to show the scene of their death.
+        entity.field_of_view = None;
+    }
+    entity.light_source = None;
+    entity.morale = None;
+    entity.needs = None;
+    entity.perception = None;
+    entity.stats = None;
+    entity.statuses = None;
+    if let Some(renderable) = entity.renderable.as_mut() {
+        renderable.char = Some('%');
+        renderable.foreground_color = Some(stage.get_color());
+        renderable.background_color = None;
+    }
+    entity.blocks_movement = false;
+    if entity.blocks_light {
+        entity.blocks_light = false;
+        if let Some(position) = entity.position {
+            game.notify_map_changed(position.x as usize, position.y as usize);
+        }
+    }
+}
+
+/// Shake the entity's morale in proportion to the health it has just lost.
+fn lose_nerve(id: usize, damage: i32, game: &mut Game) {
+    let entity = &game.entities[id];
+    let loss = match (entity.morale, &entity.body) {
+        (Some(morale), Some(body)) => morale.get_wound_loss(damage, body.total_hit_points),
+        _ => return,
+    };
+    if loss > 0 {
+        Effect::ChangeMorale(-loss).execute(id, game);
+    }
+}
+
+/// The entity's standing with a faction, recorded as neutral if there was none.
+fn get_standing_mut(entity: &mut Entity, faction: Faction) -> &mut FactionStanding {
+    let standings = entity.faction_standings.get_or_insert_with(Vec::new);
+    match standings.iter().position(|standing| standing.faction == faction) {
+        Some(index) => &mut standings[index],
+        None => {
+            standings.push(FactionStanding::new(faction, 0));
+            standings.last_mut().unwrap()
+        },
+    }
+}
+
+/// Place an item on the ground.
+fn put_down(item_id: usize, position: Position, game: &mut Game) {
+    game.map.insert_item(item_id, position.x as usize, position.y as usize);
+    game.entities[item_id].position = Some(position);
+}
+
+/// Move an item from one inventory to another.
+///
+/// Returns whether the item was moved.
+fn transfer(from_id: usize, to_id: usize, item_id: usize, game: &mut Game) -> bool {
+    if !game.can_carry(to_id, item_id) {
+        return false;
+    }
+    let is_removed = game.entities[from_id].inventory
+        .as_mut()
+        .is_some_and(|inventory| inventory.remove(item_id));
+    if !is_removed {
+        return false;
+    }
+    take_off(from_id, item_id, game);
+    if let Some(inventory) = game.entities[to_id].inventory.as_mut() {
+        inventory.add(item_id);
+    }
+    true
+}
+
+/// Learn what a consumable does by using it.
+fn identify(item_id: usize, consumable: Consumable, game: &mut Game) {
+    if game.identification.identify(consumable) {
+        let message = format!("That was a {}.", game.entities[item_id].name);
+        game.add_message(message, Color::from_rgb(255, 224, 64));
+    }
+}
+
+/// Stop wielding or wearing an item, undoing its effects on the holder.
+///
+/// Returns whether the item was equipped.
+fn take_off(id: usize, item_id: usize, game: &mut Game) -> bool {
+    let equipped = match game.entities[id].equipment.as_mut().and_then(|equipment| equipment.remove(item_id)) {
+        Some(equipped) => equipped,
+        None => return false,
+    };
+    if equipped.is_lighting {
+        let light_source = game.entities[id].light_source.take();
+        game.entities[item_id].light_source = light_source;
+    }
+    if equipped.equippable.fov_radius != 0 {
+        update_fov_radius(id, game);
+    }
+    true
+}
+
+/// Recompute an entity's sight radius from its eyes and whatever it has equipped.
+fn update_fov_radius(id: usize, game: &mut Game) {
+    let entity = &mut game.entities[id];
+    let bonus = entity.equipment.as_ref().map_or(0, |equipment| equipment.get_fov_radius());
+    if let Some(fov) = entity.field_of_view.as_mut() {
+        fov.apply_radius_bonus(bonus);
+    }
+    Effect::UpdateEntityFov.execute(id, game);
+}
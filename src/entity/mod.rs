@@ -3,20 +3,43 @@ use crate::agent;
 use agent::Agent;
 use crate::body;
 use body::Body;
+use body::Factory as BodyFactory;
+use body::PartKind;
 use crate::component;
 use component::actor::Actor;
+use component::consumable::Consumable;
+use component::container::Container;
+use component::corpse::Corpse;
+use component::equipment::{Equipment, Equippable};
+use component::experience::{Experience, Growth};
 use component::field_of_view::FieldOfView;
-use component::light_source::{LightSource, Factory as LightSourceFactory};
+use component::group::Group;
+use component::inventory::Inventory;
+use component::item::Item;
+use component::light_source::LightSource;
+use component::morale::Morale;
+use component::needs::{Kind as NeedKind, Needs};
+use component::perception::Perception;
 use component::position::Position;
 use component::renderable::{Renderable, Factory as RenderableFactory};
+use component::stats::Stats;
+use component::status::{Kind as StatusKind, Statuses};
+use crate::combat;
+use combat::dice::Dice;
 use crate::faction;
-//use faction::Faction;
+use faction::Faction;
 use faction::Standing as FactionStanding;
 use crate::map;
 use map::Map;
 use crate::species;
 use species::Species;
 
+/// The hit points gained by growing in vitality.
+const VITALITY_BONUS: i32 = 5;
+
+/// The penalty to attack for each arm that an entity has lost.
+const ARM_ATTACK_PENALTY: i32 = 2;
+
 /// The entity object that represents anything that functions in the game world.
 #[derive(Clone, Debug)]
 pub struct Entity {
@@ -34,16 +57,46 @@ pub struct Entity {
     pub actor: Option<Actor>,
     /// Something that can act autonomously.
     pub agent: Option<Agent>,
+    /// Indicates that this entity can be used up.
+    pub consumable: Option<Consumable>,
+    /// Something that holds items in its inventory.
+    pub container: Option<Container>,
+    /// The remains of a dead creature.
+    pub corpse: Option<Corpse>,
+    /// The items this entity is wielding or wearing.
+    pub equipment: Option<Equipment>,
+    /// Indicates that this entity can be wielded or worn.
+    pub equippable: Option<Equippable>,
+    /// The accumulated experience and character level of this entity.
+    pub experience: Option<Experience>,
     /// Indicates the object's ability to perceive the world around it.
     pub field_of_view: Option<FieldOfView>,
+    /// The pack the entity belongs to, if any.
+    pub group: Option<Group>,
+    /// The items carried by this entity.
+    pub inventory: Option<Inventory>,
+    /// Indicates that this entity can be picked up and carried.
+    pub item: Option<Item>,
     /// A light source attached to or possessed by this entity.
     pub light_source: Option<LightSource>,
+    /// Indicates the object's will to keep fighting.
+    pub morale: Option<Morale>,
+    /// The hunger, thirst and fatigue of this entity.
+    pub needs: Option<Needs>,
+    /// Indicates the object's ability to notice things, and avoid notice.
+    pub perception: Option<Perception>,
     /// Indicates a position of the object within the game world.
     pub position: Option<Position>,
     /// Indicates how the given object is rendered on a map.
     pub renderable: Option<Renderable>,
+    /// The combat statistics of this entity.
+    pub stats: Option<Stats>,
+    /// The statuses currently affecting this entity.
+    pub statuses: Option<Statuses>,
     /// Whether this object prevents movement.
     pub blocks_movement: bool,
+    /// Whether this object is opaque.
+    pub blocks_light: bool,
 }
 
 
@@ -60,11 +113,26 @@ impl Entity {
             body: None,
             actor: None,
             agent: None,
+            consumable: None,
+            container: None,
+            corpse: None,
+            equipment: None,
+            equippable: None,
+            experience: None,
             field_of_view: None,
+            group: None,
+            inventory: None,
+            item: None,
             light_source: None,
+            morale: None,
+            needs: None,
+            perception: None,
             position: None,
             renderable: None,
+            stats: None,
+            statuses: None,
             blocks_movement: false,
+            blocks_light: false,
         }
     }
 
@@ -74,42 +142,56 @@ impl Entity {
         self.name = entity.name.clone();
         self.species = entity.species;
         // Skip faction standings.
-        self.body = entity.body;
+        self.body = entity.body.clone();
         self.actor = entity.actor;
-        self.agent = entity.agent;
+        self.agent = entity.agent.clone();
+        self.consumable = entity.consumable;
+        self.container = entity.container;
+        self.corpse = entity.corpse.clone();
+        // Skip equipment.
+        self.equippable = entity.equippable;
+        self.experience = entity.experience;
         self.field_of_view = entity.field_of_view.clone();
+        // Skip group.
+        // Skip inventory.
+        self.item = entity.item;
         self.light_source = entity.light_source;
+        self.morale = entity.morale;
+        self.needs = entity.needs.clone();
+        self.perception = entity.perception;
         // Skip position.
         self.renderable = entity.renderable.clone();
+        self.stats = entity.stats;
+        // Skip statuses.
         self.blocks_movement = entity.blocks_movement;
+        self.blocks_light = entity.blocks_light;
     }
 
-    /// If the entity would attack another entity.
+    /// If the entity would attack another entity, judging by their factions.
     pub fn would_attack(&self, entity: &Entity) -> bool {
-        use Species::*;
         if !entity.blocks_movement {
             return false;
         }
-        match (self.species, entity.species) {
-            (Some(Orc), Some(Troll)) => false,
-            (Some(Troll), Some(Orc)) => false,
-            (Some(Human), Some(Orc)) => true,
-            (Some(Human), Some(Troll)) => true,
-            (Some(Orc), Some(Human)) => true,
-            (Some(Troll), Some(Human)) => true,
-            (Some(Troll), Some(Troll)) => false,
-            (Some(Orc), Some(Orc)) => false,
-            (Some(Goblin), Some(Chicken)) => false,
-            (Some(Kobold), Some(Chicken)) => false,
-            (Some(Goblin), Some(Moss)) => false,
-            (Some(Kobold), Some(Moss)) => false,
-            (Some(Goblin), _ ) => true,
-            (Some(Kobold), _ ) => true,
-            (Some(Chicken), Some(Moss)) => true,
-            (Some(Human), _) => true,
-            (_, Some(Goblin)) => true,
-            (_, Some(Kobold)) => true,
-            (_, _) => false,
+        match (&self.faction_standings, &entity.faction_standings) {
+            (Some(own_standings), Some(other_standings)) => faction::is_hostile(own_standings, other_standings),
+            _ => false,
+        }
+    }
+
+    /// If the entity shares a faction with another entity, and neither would attack the other.
+    pub fn is_ally_of(&self, entity: &Entity) -> bool {
+        match (&self.faction_standings, &entity.faction_standings) {
+            (Some(own_standings), Some(other_standings)) => faction::is_ally(own_standings, other_standings),
+            _ => false,
+        }
+    }
+
+    /// Change this entity's standing with a faction, joining or leaving it as thresholds are crossed.
+    pub fn change_standing(&mut self, faction: Faction, delta: i32) {
+        let standings = self.faction_standings.get_or_insert_with(Vec::new);
+        match standings.iter_mut().find(|standing| standing.faction == faction) {
+            Some(standing) => standing.standing += delta,
+            None => standings.push(FactionStanding::new(faction, delta)),
         }
     }
 
@@ -121,6 +203,86 @@ impl Entity {
         false
     }
 
+    /// The speed of this entity, slowed by any lost legs and modified by equipment and statuses.
+    pub fn get_speed(&self) -> i32 {
+        let speed = match (self.actor, &self.body) {
+            (Some(actor), Some(body)) => body.scale_by_parts(PartKind::Leg, actor.speed, actor.speed / 3),
+            (Some(actor), None) => actor.speed,
+            (None, _) => 0,
+        };
+        let speed = match &self.equipment {
+            Some(equipment) => (speed + equipment.get_speed()).max(1),
+            None => speed,
+        };
+        match &self.statuses {
+            Some(statuses) => statuses.modify_speed(speed),
+            None => speed,
+        }
+    }
+
+    /// The experience awarded for killing this entity.
+    pub fn get_experience_value(&self) -> i32 {
+        let level = self.experience.map_or(1, |experience| experience.level);
+        self.species.map_or(0, |species| species.get_experience_value() * level)
+    }
+
+    /// Grow stronger, spending a level gained.
+    pub fn grow(&mut self, growth: Growth) {
+        use Growth::*;
+        if let Some(experience) = self.experience.as_mut() {
+            experience.unspent = (experience.unspent - 1).max(0);
+        }
+        match growth {
+            Attack => {
+                if let Some(stats) = self.stats.as_mut() {
+                    stats.attack += 1;
+                }
+            },
+            Defense => {
+                if let Some(stats) = self.stats.as_mut() {
+                    stats.defense += 1;
+                }
+            },
+            Vitality => {
+                if let Some(body) = self.body.as_mut() {
+                    body.grow(VITALITY_BONUS);
+                }
+            },
+        }
+    }
+
+    /// Grow automatically to the specified level, e.g. for monsters spawned deeper.
+    pub fn advance_to_level(&mut self, level: i32) {
+        while let Some(mut experience) = self.experience {
+            if experience.level >= level {
+                break;
+            }
+            experience.level += 1;
+            experience.points = Experience::get_threshold(experience.level);
+            self.experience = Some(experience);
+            self.grow(Growth::for_level(experience.level));
+        }
+    }
+
+    /// Indicates whether this entity has a status of the specified kind.
+    pub fn has_status(&self, kind: StatusKind) -> bool {
+        self.statuses.as_ref().is_some_and(|statuses| statuses.has(kind))
+    }
+
+    /// The combat statistics of this entity, hampered by any lost arms and modified by equipment.
+    pub fn get_stats(&self) -> Option<Stats> {
+        let mut stats = self.stats?;
+        if let Some(body) = &self.body {
+            let (total, functional) = body.count_parts(PartKind::Arm);
+            stats.attack -= ARM_ATTACK_PENALTY * (total - functional) as i32;
+        }
+        if let Some(equipment) = &self.equipment {
+            stats.attack += equipment.get_attack();
+            stats.defense += equipment.get_defense();
+        }
+        Some(stats)
+    }
+
 }
 
 /// Get a "player" entity.
@@ -131,18 +293,23 @@ pub fn get_player(map: &Map) -> Entity {
         time: 0,
         speed: 12,
     });
-    player.body = Some(Body {
-        total_hit_points: 32767,
-        current_hit_points: 32767,
-    });
+    player.body = Some(BodyFactory::Humanoid.create(30).with_regeneration(Some(10)));
     player.species = Some(Species::Human);
+    let mut faction_standings = Species::Human.get_default_standings();
+    faction_standings.push(FactionStanding::new(Faction::Player, 100));
+    player.faction_standings = Some(faction_standings);
     player.field_of_view = Some(FieldOfView::new(map.get_fov(), 12));
+    player.inventory = Some(Inventory::new(26, 50));
+    player.equipment = Some(Equipment::new());
     if let Some(fov) = player.field_of_view.as_mut() {
         fov.light_walls = true;
     }
-    player.light_source = Some(LightSourceFactory::Torch.create());
+    player.perception = Some(Perception::new(0, 0));
     player.position = Some(Position::default());
     player.renderable = Some(RenderableFactory::Player.create());
+    player.stats = Some(Stats::new(4, 3, 1, Dice::new(1, 8, 0)));
+    player.experience = Some(Experience::new(1));
+    player.needs = Some(Needs::new(&[NeedKind::Hunger]));
     player.blocks_movement = true;
     player
 }
@@ -0,0 +1,72 @@
+use crate::faction;
+use faction::law;
+use crate::game;
+use game::Game;
+use crate::pack;
+
+/// Something significant that happened between two entities.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// The first entity attacked the second.
+    Attack(usize, usize),
+    /// The first entity assisted (e.g. healed) the second.
+    Assist(usize, usize),
+    /// The first entity killed the second.
+    Kill(usize, usize),
+    /// The first entity called on its pack for help against the second.
+    CallForHelp(usize, usize),
+}
+
+/// Something significant that happened between two entities.
+impl Event {
+
+    /// The entity responsible for the event.
+    pub fn get_actor_id(self) -> usize {
+        use Event::*;
+        match self {
+            Attack(actor_id, _) | Assist(actor_id, _) | Kill(actor_id, _) | CallForHelp(actor_id, _) => actor_id,
+        }
+    }
+
+    /// The entity on the receiving end of the event.
+    pub fn get_subject_id(self) -> usize {
+        use Event::*;
+        match self {
+            Attack(_, subject_id) | Assist(_, subject_id) | Kill(_, subject_id) | CallForHelp(_, subject_id) => subject_id,
+        }
+    }
+
+    /// Indicates whether the event harmed its subject.
+    pub fn is_hostile(self) -> bool {
+        use Event::*;
+        match self {
+            Attack(_, _) | Kill(_, _) => true,
+            Assist(_, _) | CallForHelp(_, _) => false,
+        }
+    }
+
+    /// Indicates whether the event helped its subject.
+    pub fn is_assist(self) -> bool {
+        matches!(self, Event::Assist(_, _))
+    }
+
+    /// Make the event known to anything nearby that might react to it.
+    pub fn publish(self, game: &mut Game) {
+        trace!("Entering Event::publish() for {:?}.", self);
+        if self.get_actor_id() == self.get_subject_id() {
+            return;
+        }
+        use Event::*;
+        match self {
+            Attack(actor_id, subject_id) => {
+                law::enforce(self, game);
+                // The victim's pack hears the fighting.
+                pack::answer(subject_id, actor_id, game);
+            },
+            CallForHelp(caller_id, threat_id) => pack::answer(caller_id, threat_id, game),
+            Assist(_, _) | Kill(_, _) => law::enforce(self, game),
+        }
+        trace!("Exiting Event::publish().");
+    }
+
+}
@@ -0,0 +1,139 @@
+use crate::effect;
+use effect::Effect;
+use crate::event;
+use event::Event;
+use crate::faction;
+use faction::{Consequence, Faction, Laws, Penalty, Standing};
+use crate::game;
+use game::Game;
+
+/// The consequences a faction's laws impose on the actor of an event.
+///
+/// Attacks and kills break laws protecting the subject's factions; assists
+/// break laws against helping them.
+pub fn judge(laws: &[Laws], event: Event, subject: &[Standing]) -> Vec<Consequence> {
+    let is_member_of = |faction: Faction| {
+        subject.iter().any(|standing| standing.faction == faction && standing.is_member())
+    };
+    laws
+        .iter()
+        .filter_map(|law| match *law {
+            Laws::DoNotAttackFaction(faction, consequence) if event.is_hostile() && is_member_of(faction) => Some(consequence),
+            Laws::DoNotAssistFaction(faction, consequence) if event.is_assist() && is_member_of(faction) => Some(consequence),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The consequences a faction's laws impose on a member whose standing has fallen.
+///
+/// A member already under a penalty is not punished again.
+pub fn judge_standing(laws: &[Laws], standing: &Standing) -> Vec<Consequence> {
+    if standing.penalty.is_some() {
+        return vec![];
+    }
+    laws
+        .iter()
+        .filter_map(|law| match *law {
+            Laws::MinimumStanding(minimum, consequence) if standing.standing < minimum => Some(consequence),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The factions whose members witnessed the event.
+///
+/// The subject always knows what happened to it; anyone else must be able to
+/// see the actor.  The player is never a witness.
+fn get_witnessing_factions(event: Event, game: &Game) -> Vec<Faction> {
+    let actor_id = event.get_actor_id();
+    let subject_id = event.get_subject_id();
+    let mut factions = vec![];
+    for entity in &game.entities {
+        if entity.id == actor_id || entity.id == game.player_id || entity.body.is_none() || entity.corpse.is_some() {
+            continue;
+        }
+        let standings = match &entity.faction_standings {
+            Some(standings) => standings,
+            None => continue,
+        };
+        if entity.id != subject_id && !game.can_see_entity(entity.id, actor_id) {
+            continue;
+        }
+        for standing in standings.iter().filter(|standing| standing.is_member()) {
+            if !factions.contains(&standing.faction) {
+                factions.push(standing.faction);
+            }
+        }
+    }
+    factions
+}
+
+/// Enforce the laws of every faction that witnessed the event upon its actor.
+pub fn enforce(event: Event, game: &mut Game) {
+    trace!("Entering law::enforce() for {:?}.", event);
+    let actor_id = event.get_actor_id();
+    let subject = match &game.entities[event.get_subject_id()].faction_standings {
+        Some(standings) => standings.clone(),
+        None => return,
+    };
+    for faction in get_witnessing_factions(event, game) {
+        let find = |game: &Game| game.entities[actor_id].faction_standings
+            .as_ref()
+            .and_then(|standings| standings.iter().find(|standing| standing.faction == faction).copied());
+        let was_member = match find(game) {
+            Some(Standing { penalty: Some(Penalty::Unperson), .. }) => continue,
+            Some(standing) => standing.is_member(),
+            None => false,
+        };
+        let laws = faction.get_laws();
+        for consequence in judge(&laws, event, &subject) {
+            Effect::ApplyConsequence(faction, consequence).execute(actor_id, game);
+        }
+        if !was_member {
+            continue;
+        }
+        if let Some(standing) = find(game) {
+            for consequence in judge_standing(&laws, &standing) {
+                Effect::ApplyConsequence(faction, consequence).execute(actor_id, game);
+            }
+        }
+    }
+    trace!("Exiting law::enforce().");
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::species;
+    use species::Species;
+
+    /// Laws protecting a faction are broken only by harming its members.
+    #[test]
+    fn judge_event() {
+        let laws = Faction::Species(Species::Orc).get_laws();
+        let orc = vec![Standing::new(Faction::Species(Species::Orc), 100)];
+        let human = vec![Standing::new(Faction::Species(Species::Human), 100)];
+        assert_eq!(judge(&laws, Event::Attack(0, 1), &orc).len(), 1);
+        assert_eq!(judge(&laws, Event::Assist(0, 1), &orc).len(), 0);
+        assert_eq!(judge(&laws, Event::Attack(0, 1), &human).len(), 0);
+        let laws = Faction::Species(Species::Human).get_laws();
+        let orc = vec![Standing::new(Faction::Species(Species::Orc), 100)];
+        assert_eq!(judge(&laws, Event::Assist(0, 1), &orc).len(), 1);
+        assert_eq!(judge(&laws, Event::CallForHelp(0, 1), &orc).len(), 0);
+    }
+
+    /// Members who fall below a minimum standing are punished once.
+    #[test]
+    fn minimum_standing() {
+        let laws = Faction::Species(Species::Goblin).get_laws();
+        let mut standing = Standing::new(Faction::Species(Species::Goblin), 70);
+        assert!(judge_standing(&laws, &standing).is_empty());
+        standing.standing = 40;
+        assert_eq!(judge_standing(&laws, &standing).len(), 1);
+        standing.penalty = Some(Penalty::Exile(100));
+        assert!(judge_standing(&laws, &standing).is_empty());
+    }
+
+}
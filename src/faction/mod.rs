@@ -1,8 +1,17 @@
 use crate::species;
 use species::Species;
 
+/// The enforcement of faction laws.
+pub mod law;
+
+/// The standing at or above which an entity counts as a member of a faction.
+pub const MEMBERSHIP_THRESHOLD: i32 = 50;
+
+/// The standing at or below which an entity is hostile to a faction's members.
+pub const HOSTILITY_THRESHOLD: i32 = -50;
+
 /// Faction class.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Faction {
     /// The player faction.
     Player,
@@ -10,6 +19,48 @@ pub enum Faction {
     Species(Species),
 }
 
+/// Faction class.
+impl Faction {
+
+    /// The name of this faction's members, collectively.
+    pub fn get_name(self) -> String {
+        match self {
+            Faction::Player => "Your allies".to_string(),
+            Faction::Species(species) => format!("The {:?}s", species),
+        }
+    }
+
+    /// The laws by which this faction judges what its members witness.
+    pub fn get_laws(self) -> Vec<Laws> {
+        use Laws::*;
+        match self {
+            Faction::Player => vec![],
+            Faction::Species(Species::Orc) => vec![
+                DoNotAttackFaction(Faction::Species(Species::Orc), Consequence::Penalty(Penalty::Pariah)),
+                DoNotAttackFaction(Faction::Species(Species::Troll), Consequence::ChangeStanding(-25)),
+            ],
+            Faction::Species(Species::Troll) => vec![
+                DoNotAttackFaction(Faction::Species(Species::Troll), Consequence::Penalty(Penalty::Pariah)),
+                DoNotAttackFaction(Faction::Species(Species::Orc), Consequence::ChangeStanding(-25)),
+            ],
+            Faction::Species(Species::Goblin) => vec![
+                DoNotAttackFaction(Faction::Species(Species::Goblin), Consequence::ChangeStanding(-30)),
+                MinimumStanding(MEMBERSHIP_THRESHOLD, Consequence::Penalty(Penalty::Exile(100))),
+            ],
+            Faction::Species(Species::Kobold) => vec![
+                DoNotAttackFaction(Faction::Species(Species::Kobold), Consequence::Penalty(Penalty::Pariah)),
+            ],
+            Faction::Species(Species::Human) => vec![
+                DoNotAttackFaction(Faction::Species(Species::Human), Consequence::ChangeStanding(-40)),
+                MinimumStanding(MEMBERSHIP_THRESHOLD, Consequence::Penalty(Penalty::Jail(20))),
+                DoNotAssistFaction(Faction::Species(Species::Orc), Consequence::ChangeStanding(-20)),
+            ],
+            Faction::Species(_) => vec![],
+        }
+    }
+
+}
+
 /// Penalties given by the faction when laws are broken.
 #[derive(Clone, Copy, Debug)]
 pub enum Penalty {
@@ -23,6 +74,22 @@ pub enum Penalty {
     Jail(i32),
 }
 
+/// Penalties given by the faction when laws are broken.
+impl Penalty {
+
+    /// What the faction considers the penalized member to be.
+    pub fn get_name(self) -> &'static str {
+        use Penalty::*;
+        match self {
+            Pariah => "a pariah",
+            Unperson => "an unperson",
+            Exile(_) => "an exile",
+            Jail(_) => "a prisoner",
+        }
+    }
+
+}
+
 /// Penalties/Rewards given by the faction when laws are broken.
 #[derive(Clone, Copy, Debug)]
 pub enum Consequence {
@@ -63,3 +130,161 @@ pub struct Standing {
     /// Any penalties the entity might be under with the faction.
     pub penalty: Option<Penalty>,
 }
+
+/// Faction membership.
+impl Standing {
+
+    /// Constructor.
+    pub fn new(faction: Faction, standing: i32) -> Standing {
+        Standing {
+            faction,
+            standing,
+            penalty: None,
+        }
+    }
+
+    /// Serve a turn of any penalty; returns whether a penalty ended.
+    pub fn tick(&mut self) -> bool {
+        let (penalty, ended) = match self.penalty {
+            Some(Penalty::Exile(turns)) if turns <= 1 => (None, true),
+            Some(Penalty::Exile(turns)) => (Some(Penalty::Exile(turns - 1)), false),
+            Some(Penalty::Jail(turns)) if turns <= 1 => (None, true),
+            Some(Penalty::Jail(turns)) => (Some(Penalty::Jail(turns - 1)), false),
+            penalty => (penalty, false),
+        };
+        self.penalty = penalty;
+        ended
+    }
+
+    /// Indicates whether the entity counts as a member of the faction.
+    pub fn is_member(&self) -> bool {
+        self.standing >= MEMBERSHIP_THRESHOLD && self.penalty.is_none()
+    }
+
+}
+
+/// The standing with the specified faction; neutral if none is recorded.
+pub fn get_standing(standings: &[Standing], faction: Faction) -> i32 {
+    standings
+        .iter()
+        .find(|standing| standing.faction == faction)
+        .map_or(0, |standing| standing.standing)
+}
+
+/// Indicates whether an entity with the first standings would attack one with the second.
+///
+/// An entity attacks members of factions it is hostile to, unless it shares a
+/// faction with them, and attacks anyone made a pariah by one of its factions.
+/// Anyone made an unperson by one of its factions is ignored entirely.
+pub fn is_hostile(attacker: &[Standing], target: &[Standing]) -> bool {
+    let is_member_of = |standings: &[Standing], faction: Faction| {
+        standings.iter().any(|standing| standing.faction == faction && standing.is_member())
+    };
+    let penalties = target
+        .iter()
+        .filter(|standing| is_member_of(attacker, standing.faction))
+        .filter_map(|standing| standing.penalty)
+        .collect::<Vec<Penalty>>();
+    if penalties.iter().any(|penalty| matches!(penalty, Penalty::Unperson)) {
+        return false;
+    }
+    if penalties.iter().any(|penalty| matches!(penalty, Penalty::Pariah)) {
+        return true;
+    }
+    let target_factions = target
+        .iter()
+        .filter(|standing| standing.is_member())
+        .map(|standing| standing.faction)
+        .collect::<Vec<Faction>>();
+    if target_factions.iter().any(|&faction| is_member_of(attacker, faction)) {
+        return false;
+    }
+    target_factions
+        .iter()
+        .any(|&faction| get_standing(attacker, faction) <= HOSTILITY_THRESHOLD)
+}
+
+/// Indicates whether entities with these standings share a faction and are at peace.
+pub fn is_ally(first: &[Standing], second: &[Standing]) -> bool {
+    let shares_faction = first
+        .iter()
+        .filter(|standing| standing.is_member())
+        .any(|standing| second.iter().any(|other| other.faction == standing.faction && other.is_member()));
+    shares_faction && !is_hostile(first, second) && !is_hostile(second, first)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Hostility follows standings, but shared membership keeps the peace.
+    #[test]
+    fn hostility() {
+        let orc = vec![
+            Standing::new(Faction::Species(Species::Orc), 100),
+            Standing::new(Faction::Species(Species::Human), -100),
+        ];
+        let human = vec![
+            Standing::new(Faction::Species(Species::Human), 100),
+            Standing::new(Faction::Species(Species::Orc), -100),
+        ];
+        let turncoat = vec![
+            Standing::new(Faction::Species(Species::Human), 100),
+            Standing::new(Faction::Species(Species::Orc), 60),
+        ];
+        assert!(is_hostile(&orc, &human));
+        assert!(is_hostile(&human, &orc));
+        assert!(!is_hostile(&orc, &orc));
+        assert!(!is_hostile(&orc, &turncoat));
+        assert!(!is_hostile(&human, &turncoat));
+        assert!(!is_hostile(&orc, &[]));
+        assert!(is_ally(&orc, &orc));
+        assert!(is_ally(&orc, &turncoat));
+        assert!(!is_ally(&orc, &human));
+        assert!(!is_ally(&orc, &[]));
+    }
+
+    /// Exile and jail are served out; other penalties are permanent.
+    #[test]
+    fn tick() {
+        let mut standing = Standing::new(Faction::Species(Species::Goblin), 100);
+        standing.penalty = Some(Penalty::Exile(2));
+        assert!(!standing.is_member());
+        assert!(!standing.tick());
+        assert!(standing.tick());
+        assert!(standing.is_member());
+        standing.penalty = Some(Penalty::Pariah);
+        assert!(!standing.tick());
+        assert!(standing.penalty.is_some());
+    }
+
+    /// Pariahs are attacked on sight by the members of the faction.
+    #[test]
+    fn pariah() {
+        let orc = vec![
+            Standing::new(Faction::Species(Species::Orc), 100),
+        ];
+        let mut outcast = Standing::new(Faction::Species(Species::Orc), 100);
+        outcast.penalty = Some(Penalty::Pariah);
+        assert!(is_hostile(&orc, &[outcast]));
+        assert!(!is_hostile(&[outcast], &orc));
+    }
+
+    /// Unpersons are ignored, even by those who would otherwise attack them.
+    #[test]
+    fn unperson() {
+        let orc = vec![
+            Standing::new(Faction::Species(Species::Orc), 100),
+            Standing::new(Faction::Species(Species::Human), -100),
+        ];
+        let mut outcast = Standing::new(Faction::Species(Species::Orc), 0);
+        outcast.penalty = Some(Penalty::Unperson);
+        let human = vec![
+            Standing::new(Faction::Species(Species::Human), 100),
+            outcast,
+        ];
+        assert!(!is_hostile(&orc, &human));
+    }
+
+}
@@ -1,17 +1,36 @@
+use crate::agent;
+use agent::behavior::{BehaviorTrees, get_behavior_trees};
+use crate::component;
+use component::perception;
 use crate::effect;
 use effect::Effect;
 use crate::entity;
 use entity::Entity;
 use entity::get_player;
+use crate::item;
+use item::Factory as ItemFactory;
+use item::identification::Identification;
+use crate::loot;
+use loot::{LootTables, get_loot_tables};
+use std::collections::HashMap;
+use bear_lib_terminal::Color;
+use tcod::map::Map as FovMap;
 use crate::map;
 use map::Map;
 use map::get_map;
+use map::lighting::LightMap;
+use crate::messages;
+use messages::Messages;
+use crate::pathfinding;
+use pathfinding::dijkstra::{DijkstraMap, Kind as DijkstraMapKind};
 use crate::scheduler;
 use scheduler::Scheduler;
 use crate::seed;
 use seed::SeedType;
 use seed::RngType;
 use seed::get_rng;
+use crate::species;
+use species::Species;
 use crate::settings;
 use settings::Settings;
 use settings::get_settings;
@@ -26,6 +45,12 @@ pub struct Game {
     pub input_domain: InputDomain,
     /// The game map.
     pub map: Map,
+    /// The light falling on the game map this turn.
+    pub light_map: LightMap,
+    /// The Dijkstra maps shared by agents this turn.
+    pub dijkstra_maps: HashMap<DijkstraMapKind, DijkstraMap>,
+    /// The maps leading away from each set of threats agents can see this turn.
+    pub flee_maps: HashMap<Vec<usize>, DijkstraMap>,
     /// All entities in the game.
     pub entities: Vec<Entity>,
     /// The player entity ID.
@@ -38,6 +63,14 @@ pub struct Game {
     pub rng: RngType,
     /// Which turn of the game we're on.
     pub turns: usize,
+    /// The message log.
+    pub messages: Messages,
+    /// What the player has learned of the consumables in this game.
+    pub identification: Identification,
+    /// The tables deciding what creatures drop and containers hold.
+    pub loot_tables: LootTables,
+    /// The behavior trees agents follow.
+    pub behavior_trees: BehaviorTrees,
     /// Whether or not we should advance the clock.
     pub should_advance: bool,
     /// Whether or not we should continue.
@@ -56,6 +89,267 @@ impl Game {
             .collect()
     }
 
+    /// Get the items lying at the specified location.
+    pub fn get_items(&self, x: i32, y: i32) -> Vec<&Entity> {
+        self.map
+            .get_items(x as usize, y as usize)
+            .unwrap_or_default()
+            .iter()
+            .map(|&x| &self.entities[x] )
+            .collect()
+    }
+
+    /// Get the total weight of the items carried by the entity.
+    pub fn get_carried_weight(&self, id: usize) -> i32 {
+        match &self.entities[id].inventory {
+            Some(inventory) => inventory.items
+                .iter()
+                .filter_map(|&item_id| self.entities[item_id].item)
+                .map(|item| item.weight)
+                .sum(),
+            None => 0,
+        }
+    }
+
+    /// Indicates whether the entity has room to carry the specified item.
+    pub fn can_carry(&self, id: usize, item_id: usize) -> bool {
+        match (&self.entities[id].inventory, self.entities[item_id].item) {
+            (Some(inventory), Some(item)) => inventory.can_add(self.get_carried_weight(id), item.weight),
+            _ => false,
+        }
+    }
+
+    /// The name of the entity as the player knows it.
+    pub fn get_item_name(&self, id: usize) -> String {
+        let entity = &self.entities[id];
+        entity.consumable
+            .and_then(|consumable| self.identification.get_name(consumable))
+            .unwrap_or_else(|| entity.name.clone())
+    }
+
+    /// Indicates whether the entity can reach the container, i.e. shares or is adjacent to its position.
+    pub fn can_reach(&self, id: usize, container_id: usize) -> bool {
+        match (self.entities[id].position, self.entities[container_id].position) {
+            (Some(position1), Some(position2)) => (position1.x - position2.x).abs() <= 1 && (position1.y - position2.y).abs() <= 1,
+            _ => false,
+        }
+    }
+
+    /// Get the nearest container the entity can reach, preferring one at its own position.
+    pub fn get_container_near(&self, id: usize) -> Option<usize> {
+        let position = self.entities[id].position?;
+        let here = self.map
+            .get_entities(position.x as usize, position.y as usize)
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<usize>>();
+        let around = self.map.get_entities_around(position.x as usize, position.y as usize);
+        here.into_iter()
+            .chain(around)
+            .find(|&other_id| other_id != id && self.entities[other_id].container.is_some())
+    }
+
+    /// Create an item in the entity's inventory, returning its ID.
+    pub fn give_item(&mut self, id: usize, item_factory: ItemFactory) -> usize {
+        let mut item = item_factory.create();
+        let item_id = self.entities.len();
+        item.id = item_id;
+        self.entities.push(item);
+        if let Some(inventory) = self.entities[id].inventory.as_mut() {
+            inventory.add(item_id);
+        }
+        item_id
+    }
+
+    /// Add a message to the message log.
+    pub fn add_message(&mut self, text: String, color: Color) {
+        let turn = self.turns;
+        self.messages.add(text, color, turn);
+    }
+
+    /// Indicates whether light passes through the specified location.
+    pub fn is_transparent(&self, x: usize, y: usize) -> bool {
+        !self.map.get_tile(x, y).blocks_light
+            && !self.get_entities(x as i32, y as i32)
+                .iter()
+                .any(|entity| entity.blocks_light)
+    }
+
+    /// Indicates whether an observer can see another entity.
+    ///
+    /// The target must be within the observer's field of view and either
+    /// adjacent or sufficiently lit, taking darkvision and stealth into account.
+    pub fn can_see_entity(&self, observer_id: usize, target_id: usize) -> bool {
+        if observer_id == target_id {
+            return true;
+        }
+        let observer = &self.entities[observer_id];
+        let target = &self.entities[target_id];
+        let (observer_position, target_position) = match (observer.position, target.position) {
+            (Some(observer_position), Some(target_position)) => (observer_position, target_position),
+            _ => return false,
+        };
+        if let Some(fov) = &observer.field_of_view {
+            if !fov.map.lock().unwrap().is_in_fov(target_position.x, target_position.y) {
+                return false;
+            }
+        } else {
+            return false;
+        }
+        if observer_position.distance_to(&target_position) < 2.0 {
+            return true;
+        }
+        let light = self.light_map.get_intensity_at(target_position.x, target_position.y);
+        perception::is_visible(light, observer.perception, target.perception)
+    }
+
+    /// Indicates whether the player has died.
+    pub fn is_player_dead(&self) -> bool {
+        self.entities[self.player_id].corpse.is_some()
+    }
+
+    /// Indicates whether the entity can see anything that would attack it.
+    pub fn can_see_hostile(&self, id: usize) -> bool {
+        let entity = &self.entities[id];
+        self.entities
+            .iter()
+            .filter(|other| other.id != id && other.would_attack(entity))
+            .any(|other| self.can_see_entity(id, other.id))
+    }
+
+    /// The entities the specified entity can see that it would fight, or that would fight it.
+    pub fn get_visible_enemies(&self, id: usize) -> Vec<usize> {
+        let entity = &self.entities[id];
+        self.entities
+            .iter()
+            .filter(|other| other.id != id && (other.would_attack(entity) || entity.would_attack(other)))
+            .filter(|other| self.can_see_entity(id, other.id))
+            .map(|other| other.id)
+            .collect()
+    }
+
+    /// The entities the specified entity can see that would fight it.
+    pub fn get_visible_threats(&self, id: usize) -> Vec<usize> {
+        let entity = &self.entities[id];
+        self.entities
+            .iter()
+            .filter(|other| other.id != id && other.would_attack(entity))
+            .filter(|other| self.can_see_entity(id, other.id))
+            .map(|other| other.id)
+            .collect()
+    }
+
+    /// The living entities the specified entity can see that share a faction with it.
+    pub fn get_visible_allies(&self, id: usize) -> Vec<usize> {
+        let entity = &self.entities[id];
+        self.entities
+            .iter()
+            .filter(|other| other.id != id && other.body.is_some() && other.corpse.is_none() && entity.is_ally_of(other))
+            .filter(|other| self.can_see_entity(id, other.id))
+            .map(|other| other.id)
+            .collect()
+    }
+
+    /// Get a field-of-view map reflecting tiles and opaque entities.
+    pub fn get_fov_map(&self) -> FovMap {
+        let mut fov_map = self.map.get_fov();
+        for entity in &self.entities {
+            if entity.blocks_light {
+                if let Some(position) = entity.position {
+                    let walkable = !self.map.get_tile_at_position(&position).blocks_movement;
+                    fov_map.set(position.x, position.y, false, walkable);
+                }
+            }
+        }
+        fov_map
+    }
+
+    /// Recompute the light map for the current turn.
+    pub fn update_light_map(&mut self) {
+        trace!("Entering Game::update_light_map().");
+        self.light_map = LightMap::compute(self);
+        trace!("Exiting Game::update_light_map().");
+    }
+
+    /// Recompute the Dijkstra maps shared by agents this turn.
+    pub fn update_dijkstra_maps(&mut self) {
+        trace!("Entering Game::update_dijkstra_maps().");
+        let (width, height) = (self.map.width, self.map.height);
+        let get_cost = |x: i32, y: i32| pathfinding::get_terrain_cost(self, x, y);
+        let player_goals = self.entities[self.player_id].position
+            .iter()
+            .map(|position| (position.x, position.y, 0))
+            .collect::<Vec<_>>();
+        let food_goals = self.entities
+            .iter()
+            .filter(|entity| entity.species == Some(Species::Moss))
+            .filter_map(|entity| entity.position)
+            .map(|position| (position.x, position.y, 0))
+            .collect::<Vec<_>>();
+        let water_goals = (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, y)))
+            .filter(|&(x, y)| self.map.get_tile(x, y).is_drinkable)
+            .map(|(x, y)| (x as i32, y as i32, 0))
+            .collect::<Vec<_>>();
+        let distance_to_player = DijkstraMap::compute(width, height, &player_goals, get_cost);
+        let distance_to_food = DijkstraMap::compute(width, height, &food_goals, get_cost);
+        let distance_to_water = DijkstraMap::compute(width, height, &water_goals, get_cost);
+        let safety = distance_to_player.invert(1.2, get_cost);
+        let forage = DijkstraMap::combine(&[
+            (&distance_to_food, 1.0),
+            (&safety, 0.5),
+        ]);
+        let mut dijkstra_maps = HashMap::new();
+        dijkstra_maps.insert(DijkstraMapKind::DistanceToPlayer, distance_to_player);
+        dijkstra_maps.insert(DijkstraMapKind::DistanceToFood, distance_to_food);
+        dijkstra_maps.insert(DijkstraMapKind::DistanceToWater, distance_to_water);
+        dijkstra_maps.insert(DijkstraMapKind::Safety, safety);
+        dijkstra_maps.insert(DijkstraMapKind::Forage, forage);
+        self.dijkstra_maps = dijkstra_maps;
+        trace!("Exiting Game::update_dijkstra_maps().");
+    }
+
+    /// Recompute the flee maps, once for each set of threats that agents can see this turn.
+    ///
+    /// The threats in sight of each entity are looked up once a turn, and shared with the morale upkeep.
+    pub fn update_flee_maps(&mut self, threats: &[(usize, Vec<usize>)]) {
+        trace!("Entering Game::update_flee_maps().");
+        let mut flee_maps = HashMap::new();
+        for (id, threat_ids) in threats {
+            if self.entities[*id].agent.is_none() || threat_ids.is_empty() || flee_maps.contains_key(threat_ids) {
+                continue;
+            }
+            if let Some(flee_map) = agent::get_flee_map(threat_ids, self) {
+                flee_maps.insert(threat_ids.clone(), flee_map);
+            }
+        }
+        self.flee_maps = flee_maps;
+        trace!("Exiting Game::update_flee_maps().");
+    }
+
+    /// Notify fields of view that the map has changed at the specified location.
+    ///
+    /// Each FOV map is a snapshot, so the cell is rewritten in every one of
+    /// them; those that had the cell within range are then recomputed.
+    pub fn notify_map_changed(&mut self, x: usize, y: usize) {
+        trace!("Entering Game::notify_map_changed().");
+        let transparent = self.is_transparent(x, y);
+        let walkable = !self.map.get_tile(x, y).blocks_movement;
+        let mut dirty_ids = vec![];
+        for (id, entity) in self.entities.iter_mut().enumerate() {
+            if let Some(fov) = entity.field_of_view.as_mut() {
+                fov.set_cell(x as i32, y as i32, transparent, walkable);
+                if fov.dirty {
+                    dirty_ids.push(id);
+                }
+            }
+        }
+        for id in dirty_ids {
+            Effect::UpdateEntityFov.execute(id, self);
+        }
+        trace!("Exiting Game::notify_map_changed().");
+    }
+
 }
 
 /// Setup and run the main game loop.
@@ -78,19 +372,28 @@ pub fn run() {
     let width = ui.settings.map.width;
     let height = ui.settings.map.height;
     let mut entities = Vec::new();
-    let (map, position) = get_map(seed, &mut rng, width, height, 0, &mut entities);
+    let loot_tables = get_loot_tables();
+    let (map, position) = get_map(seed, &mut rng, width, height, 0, &loot_tables, &mut entities);
     let mut player = get_player(&map);
     let player_position = player.position.unwrap();
     let next_id = entities.len();
+    let light_map = LightMap::new(map.width, map.height);
     let mut game = Game {
         input_domain: InputDomain::Explore,
         map: map,
+        light_map,
+        dijkstra_maps: HashMap::new(),
+        flee_maps: HashMap::new(),
         entities: entities,
         player_id: next_id,
         settings: get_settings(),
         seed: seed,
         rng: rng,
         turns: 0,
+        messages: Messages::new(),
+        identification: Identification::new(seed),
+        loot_tables,
+        behavior_trees: get_behavior_trees(),
         should_advance: false,
         should_continue: true,
     };
@@ -99,11 +402,27 @@ pub fn run() {
     game.entities.push(player);
     Effect::MoveEntity(player_position, position)
         .execute(player_id, &mut game);
+    let torch_id = game.give_item(player_id, ItemFactory::Torch);
+    Effect::EquipItem(torch_id)
+        .execute(player_id, &mut game);
     scheduler.feed(&mut game.entities);
     ui.refresh();
+    let mut is_new_turn = true;
     while !ui.is_closed() {
+        if game.is_player_dead() {
+            ui.show_game_over(player_id, &game);
+            ui.close();
+            return;
+        }
         if let Some(next_id) = scheduler.next(&game.entities) {
             if next_id == player_id {
+                if is_new_turn {
+                    scheduler.begin_turn(&mut game);
+                    is_new_turn = false;
+                    if game.is_player_dead() {
+                        continue;
+                    }
+                }
                 ui.render(player_id, &game);
                 debug!("Player ID = Next ID.");
                 game.should_advance = false;
@@ -114,6 +433,7 @@ pub fn run() {
                     return;
                 } else if game.should_advance {
                     debug!("Feeding.");
+                    is_new_turn = true;
                     scheduler.feed(&mut game.entities);
                 }
             } else {
@@ -126,3 +446,29 @@ pub fn run() {
         }
     }
 }
+
+/// Get a small game of open floor with no entities, for tests.
+#[cfg(test)]
+pub fn get_test_game(width: usize, height: usize) -> Game {
+    use crate::tile::Tile;
+    let seed: SeedType = [0; 32];
+    Game {
+        input_domain: InputDomain::Explore,
+        map: Map::new(vec![vec![Tile::floor(); height]; width]),
+        light_map: LightMap::new(width, height),
+        dijkstra_maps: HashMap::new(),
+        flee_maps: HashMap::new(),
+        entities: vec![],
+        player_id: 0,
+        settings: Settings::new(),
+        seed,
+        rng: get_rng(seed),
+        turns: 0,
+        messages: Messages::new(),
+        identification: Identification::new(seed),
+        loot_tables: LootTables::default(),
+        behavior_trees: BehaviorTrees::default(),
+        should_advance: false,
+        should_continue: true,
+    }
+}
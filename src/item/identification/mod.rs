@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+use rand::seq::SliceRandom;
+use crate::component;
+use component::consumable::{Consumable, Kind as ConsumableKind, UseEffect};
+use crate::seed;
+use seed::{SeedType, get_rng};
+
+/// The use effects found on potions.
+pub const POTION_EFFECTS: [UseEffect; 2] = [
+    UseEffect::Healing,
+    UseEffect::Light,
+];
+
+/// The use effects found on scrolls.
+pub const SCROLL_EFFECTS: [UseEffect; 3] = [
+    UseEffect::Teleportation,
+    UseEffect::MagicMapping,
+    UseEffect::Moss,
+];
+
+/// The ways an unidentified potion might look.
+const POTION_APPEARANCES: [&str; 6] = [
+    "Murky",
+    "Fizzing",
+    "Golden",
+    "Smoky",
+    "Violet",
+    "Bubbling",
+];
+
+/// The labels an unidentified scroll might bear.
+const SCROLL_APPEARANCES: [&str; 6] = [
+    "ZELGO MER",
+    "FOOBIE BLETCH",
+    "XIXAXA XOXAXA",
+    "ELAM EBOW",
+    "VERR YED HORRE",
+    "KERNOD WEL",
+];
+
+/// What the player has learned of the consumables in this game.
+#[derive(Clone, Debug)]
+pub struct Identification {
+    /// The appearance of each magical use effect in this game.
+    pub appearances: HashMap<UseEffect, &'static str>,
+    /// The use effects that have been identified.
+    pub known: HashSet<UseEffect>,
+}
+
+/// What the player has learned of the consumables in this game.
+impl Identification {
+
+    /// Constructor; appearances are shuffled by the game's seed.
+    pub fn new(seed: SeedType) -> Identification {
+        trace!("Entering Identification::new().");
+        let mut rng = get_rng(seed);
+        let mut potion_appearances = POTION_APPEARANCES.to_vec();
+        let mut scroll_appearances = SCROLL_APPEARANCES.to_vec();
+        potion_appearances.shuffle(&mut rng);
+        scroll_appearances.shuffle(&mut rng);
+        let mut appearances = HashMap::new();
+        for (use_effect, appearance) in POTION_EFFECTS.iter().zip(potion_appearances) {
+            appearances.insert(*use_effect, appearance);
+        }
+        for (use_effect, appearance) in SCROLL_EFFECTS.iter().zip(scroll_appearances) {
+            appearances.insert(*use_effect, appearance);
+        }
+        Identification {
+            appearances,
+            known: HashSet::new(),
+        }
+    }
+
+    /// Indicates whether the consumable's use effect is known.
+    pub fn is_known(&self, consumable: Consumable) -> bool {
+        consumable.kind == ConsumableKind::Food || self.known.contains(&consumable.use_effect)
+    }
+
+    /// Learn the consumable's use effect; returns whether it was previously unknown.
+    pub fn identify(&mut self, consumable: Consumable) -> bool {
+        if self.is_known(consumable) {
+            return false;
+        }
+        self.known.insert(consumable.use_effect)
+    }
+
+    /// The name of the consumable as the player knows it, or `None` for its true name.
+    pub fn get_name(&self, consumable: Consumable) -> Option<String> {
+        if self.is_known(consumable) {
+            return None;
+        }
+        let appearance = self.appearances.get(&consumable.use_effect)?;
+        match consumable.kind {
+            ConsumableKind::Potion => Some(format!("{} Potion", appearance)),
+            ConsumableKind::Scroll => Some(format!("Scroll labeled {}", appearance)),
+            ConsumableKind::Food => None,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Appearances are stable for a seed, distinct, and dropped once identified.
+    #[test]
+    fn identify() {
+        let seed = [7; 32];
+        let mut identification = Identification::new(seed);
+        let other = Identification::new(seed);
+        let healing = Consumable::new(ConsumableKind::Potion, UseEffect::Healing);
+        let light = Consumable::new(ConsumableKind::Potion, UseEffect::Light);
+        assert_eq!(identification.get_name(healing), other.get_name(healing));
+        assert_ne!(identification.get_name(healing), identification.get_name(light));
+        assert!(identification.identify(healing));
+        assert!(!identification.identify(healing));
+        assert_eq!(None, identification.get_name(healing));
+        assert!(identification.get_name(light).is_some());
+    }
+
+    /// Food is always known.
+    #[test]
+    fn food() {
+        let mut identification = Identification::new([0; 32]);
+        let ration = Consumable::new(ConsumableKind::Food, UseEffect::Nourishment(5));
+        assert!(identification.is_known(ration));
+        assert!(!identification.identify(ration));
+        assert_eq!(None, identification.get_name(ration));
+    }
+
+}
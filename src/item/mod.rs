@@ -0,0 +1,202 @@
+use crate::component;
+use component::consumable::{Consumable, Kind as ConsumableKind, UseEffect};
+use component::equipment::{Equippable, Slot};
+use component::item::Item;
+use component::light_source::Factory as LightSourceFactory;
+use component::renderable::Factory as RenderableFactory;
+use crate::entity;
+use entity::Entity;
+
+/// Keeping track of what the player has learned about consumables.
+pub mod identification;
+
+/// Factory.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Factory {
+    /// A rock.
+    Rock,
+    /// A dagger.
+    Dagger,
+    /// A bone.
+    Bone,
+    /// A torch.
+    Torch,
+    /// Leather armor.
+    LeatherArmor,
+    /// A helmet.
+    Helmet,
+    /// A potion of healing.
+    PotionOfHealing,
+    /// A potion of light.
+    PotionOfLight,
+    /// A scroll of teleportation.
+    ScrollOfTeleportation,
+    /// A scroll of magic mapping.
+    ScrollOfMagicMapping,
+    /// A scroll of moss.
+    ScrollOfMoss,
+    /// A ration of food.
+    Ration,
+    /// A loaf of bread.
+    Bread,
+    /// A mushroom, picked; best not eaten.
+    Mushroom,
+}
+
+/// Factory.
+impl Factory {
+
+    /// Create an entity of the specified type.
+    pub fn create(&self) -> Entity {
+        use Factory::*;
+        match self {
+            Rock => {
+                let mut rock = Entity::new("Rock".to_string());
+                self.apply(&mut rock);
+                rock
+            },
+            Dagger => {
+                let mut dagger = Entity::new("Dagger".to_string());
+                self.apply(&mut dagger);
+                dagger
+            },
+            Bone => {
+                let mut bone = Entity::new("Bone".to_string());
+                self.apply(&mut bone);
+                bone
+            },
+            Torch => {
+                let mut torch = Entity::new("Torch".to_string());
+                self.apply(&mut torch);
+                torch
+            },
+            LeatherArmor => {
+                let mut leather_armor = Entity::new("Leather Armor".to_string());
+                self.apply(&mut leather_armor);
+                leather_armor
+            },
+            Helmet => {
+                let mut helmet = Entity::new("Helmet".to_string());
+                self.apply(&mut helmet);
+                helmet
+            },
+            PotionOfHealing => {
+                let mut potion = Entity::new("Potion of Healing".to_string());
+                self.apply(&mut potion);
+                potion
+            },
+            PotionOfLight => {
+                let mut potion = Entity::new("Potion of Light".to_string());
+                self.apply(&mut potion);
+                potion
+            },
+            ScrollOfTeleportation => {
+                let mut scroll = Entity::new("Scroll of Teleportation".to_string());
+                self.apply(&mut scroll);
+                scroll
+            },
+            ScrollOfMagicMapping => {
+                let mut scroll = Entity::new("Scroll of Magic Mapping".to_string());
+                self.apply(&mut scroll);
+                scroll
+            },
+            ScrollOfMoss => {
+                let mut scroll = Entity::new("Scroll of Moss".to_string());
+                self.apply(&mut scroll);
+                scroll
+            },
+            Ration => {
+                let mut ration = Entity::new("Ration".to_string());
+                self.apply(&mut ration);
+                ration
+            },
+            Bread => {
+                let mut bread = Entity::new("Bread".to_string());
+                self.apply(&mut bread);
+                bread
+            },
+            Mushroom => {
+                let mut mushroom = Entity::new("Mushroom".to_string());
+                self.apply(&mut mushroom);
+                mushroom
+            },
+        }
+    }
+
+    /// Apply the specified type to an entity.
+    pub fn apply(&self, entity: &mut Entity) {
+        use Factory::*;
+        match self {
+            Rock => {
+                entity.item = Some(Item::new(2));
+                entity.renderable = Some(RenderableFactory::Rock.create());
+            },
+            Dagger => {
+                entity.item = Some(Item::new(3));
+                entity.equippable = Some(Equippable::new(Slot::MainHand).with_attack(2));
+                entity.renderable = Some(RenderableFactory::Dagger.create());
+            },
+            Bone => {
+                entity.item = Some(Item::new(1));
+                entity.renderable = Some(RenderableFactory::Bone.create());
+            },
+            Torch => {
+                entity.item = Some(Item::new(2));
+                entity.equippable = Some(Equippable::new(Slot::OffHand));
+                entity.light_source = Some(LightSourceFactory::Torch.create());
+                entity.renderable = Some(RenderableFactory::Torch.create());
+            },
+            LeatherArmor => {
+                entity.item = Some(Item::new(10));
+                entity.equippable = Some(Equippable::new(Slot::Body).with_defense(2).with_speed(-1));
+                entity.renderable = Some(RenderableFactory::LeatherArmor.create());
+            },
+            Helmet => {
+                entity.item = Some(Item::new(4));
+                entity.equippable = Some(Equippable::new(Slot::Head).with_defense(1).with_fov_radius(-2));
+                entity.renderable = Some(RenderableFactory::Helmet.create());
+            },
+            PotionOfHealing => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Potion, UseEffect::Healing));
+                entity.renderable = Some(RenderableFactory::Potion.create());
+            },
+            PotionOfLight => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Potion, UseEffect::Light));
+                entity.renderable = Some(RenderableFactory::Potion.create());
+            },
+            ScrollOfTeleportation => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Scroll, UseEffect::Teleportation));
+                entity.renderable = Some(RenderableFactory::Scroll.create());
+            },
+            ScrollOfMagicMapping => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Scroll, UseEffect::MagicMapping));
+                entity.renderable = Some(RenderableFactory::Scroll.create());
+            },
+            ScrollOfMoss => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Scroll, UseEffect::Moss));
+                entity.renderable = Some(RenderableFactory::Scroll.create());
+            },
+            Ration => {
+                entity.item = Some(Item::new(2));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Food, UseEffect::Nourishment(5)));
+                entity.renderable = Some(RenderableFactory::Food.create());
+            },
+            Bread => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Food, UseEffect::Nourishment(3)));
+                entity.renderable = Some(RenderableFactory::Food.create());
+            },
+            Mushroom => {
+                entity.item = Some(Item::new(1));
+                entity.consumable = Some(Consumable::new(ConsumableKind::Food, UseEffect::Poison(8)));
+                entity.renderable = Some(RenderableFactory::Mushroom.create());
+            },
+        }
+    }
+
+}
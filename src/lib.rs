@@ -24,7 +24,6 @@
 /// EXTERNAL CRATES
 
 /// A rustic FFI for BearLibTerminal;
-#[link(name = "lib/libBearLibTerminal.dylib")]
 extern crate bear_lib_terminal;
 
 /// A Rust library providing a lightweight logging facade.
@@ -89,6 +88,12 @@ pub mod faction;
 /// The game structure, run loop, etc.
 pub mod game;
 
+/// Items are things that can be picked up and carried.
+pub mod item;
+
+/// Loot tables decide what creatures drop and what containers hold.
+pub mod loot;
+
 /// The map, the current slice of the game world.
 pub mod map;
 
@@ -98,6 +103,12 @@ pub mod math;
 /// The message panel.
 pub mod messages;
 
+/// Packs are groups of creatures that hunt together.
+pub mod pack;
+
+/// Pathfinding over the map.
+pub mod pathfinding;
+
 /// The scheduler that determines when and who will be able to issue commands.
 pub mod scheduler;
 
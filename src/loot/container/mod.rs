@@ -0,0 +1,63 @@
+use crate::component;
+use component::container::{Container, Kind as ContainerKind};
+use component::inventory::Inventory;
+use component::renderable::Factory as RenderableFactory;
+use crate::entity;
+use entity::Entity;
+
+/// Factory.
+#[derive(Clone, Copy, Debug)]
+pub enum Factory {
+    /// A chest.
+    Chest,
+    /// A sack.
+    Sack,
+}
+
+/// Factory.
+impl Factory {
+
+    /// Create an entity of the specified type.
+    pub fn create(&self) -> Entity {
+        use Factory::*;
+        match self {
+            Chest => {
+                let mut chest = Entity::new("Chest".to_string());
+                self.apply(&mut chest);
+                chest
+            },
+            Sack => {
+                let mut sack = Entity::new("Sack".to_string());
+                self.apply(&mut sack);
+                sack
+            },
+        }
+    }
+
+    /// Apply the specified type to an entity.
+    pub fn apply(&self, entity: &mut Entity) {
+        use Factory::*;
+        match self {
+            Chest => {
+                entity.container = Some(Container::new(ContainerKind::Chest));
+                entity.inventory = Some(Inventory::new(12, 100));
+                entity.renderable = Some(RenderableFactory::Chest.create());
+            },
+            Sack => {
+                entity.container = Some(Container::new(ContainerKind::Sack));
+                entity.inventory = Some(Inventory::new(6, 20));
+                entity.renderable = Some(RenderableFactory::Sack.create());
+            },
+        }
+    }
+
+    /// The kind of container created.
+    pub fn get_kind(self) -> ContainerKind {
+        use Factory::*;
+        match self {
+            Chest => ContainerKind::Chest,
+            Sack => ContainerKind::Sack,
+        }
+    }
+
+}
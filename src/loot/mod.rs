@@ -0,0 +1,167 @@
+use std::io::Read;
+use std::fs::File;
+use std::error::Error;
+use rand::Rng;
+use crate::component;
+use component::container::Kind as ContainerKind;
+use crate::item;
+use item::Factory as ItemFactory;
+use crate::species;
+use species::Species;
+
+/// Containers, such as chests and sacks.
+pub mod container;
+
+/// The filename.
+pub const LOOT_FILENAME: &str = "resources/azymus/loot/loot.json";
+
+/// The things that produce loot.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Source {
+    /// A creature of the specified species, on death.
+    Creature(Species),
+    /// A container of the specified kind, when generated.
+    Container(ContainerKind),
+}
+
+/// A possible result of a roll on a loot table.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct Entry {
+    /// The item produced, or `None` for nothing.
+    pub item: Option<ItemFactory>,
+    /// The relative likelihood of this entry.
+    pub weight: i32,
+}
+
+/// A table of weighted entries, rolled a number of times.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Table {
+    /// What this table produces loot for.
+    pub source: Source,
+    /// The shallowest depth at which this table applies.
+    pub min_depth: i32,
+    /// The deepest depth at which this table applies, if any.
+    pub max_depth: Option<i32>,
+    /// The number of times this table is rolled.
+    pub rolls: i32,
+    /// The possible results.
+    pub entries: Vec<Entry>,
+}
+
+/// A table of weighted entries, rolled a number of times.
+impl Table {
+
+    /// Indicates whether this table applies to the source at the specified depth.
+    pub fn applies_to(&self, source: Source, depth: i32) -> bool {
+        self.source == source
+            && depth >= self.min_depth
+            && self.max_depth.is_none_or(|max_depth| depth <= max_depth)
+    }
+
+    /// Roll once on this table.
+    pub fn roll_once<R: Rng>(&self, rng: &mut R) -> Option<ItemFactory> {
+        let total: i32 = self.entries.iter().map(|entry| entry.weight.max(0)).sum();
+        if total <= 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0, total);
+        for entry in &self.entries {
+            let weight = entry.weight.max(0);
+            if roll < weight {
+                return entry.item;
+            }
+            roll -= weight;
+        }
+        None
+    }
+
+}
+
+/// All of the loot tables.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LootTables {
+    /// The tables.
+    pub tables: Vec<Table>,
+}
+
+/// All of the loot tables.
+impl LootTables {
+
+    /// Roll every table that applies to the source at the specified depth.
+    pub fn roll<R: Rng>(&self, source: Source, depth: i32, rng: &mut R) -> Vec<ItemFactory> {
+        let mut result = vec![];
+        for table in self.tables.iter().filter(|table| table.applies_to(source, depth)) {
+            for _ in 0..table.rolls {
+                if let Some(item_factory) = table.roll_once(rng) {
+                    result.push(item_factory);
+                }
+            }
+        }
+        result
+    }
+
+}
+
+/// Load the loot tables.
+pub fn load() -> Result<LootTables, Box<dyn Error>> {
+    let mut data = String::new();
+    let mut file = File::open(LOOT_FILENAME)?;
+    file.read_to_string(&mut data)?;
+    let result = serde_json::from_str::<LootTables>(&data)?;
+    Ok(result)
+}
+
+/// Load the loot tables, or go without loot if they can't be loaded.
+pub fn get_loot_tables() -> LootTables {
+    match load() {
+        Ok(loot_tables) => loot_tables,
+        Err(error) => {
+            warn!("Could not load loot tables: {}", error);
+            LootTables::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::seed::get_rng;
+
+    /// The shipped loot tables parse.
+    #[test]
+    fn parse() {
+        let data = include_str!("../../resources/azymus/loot/loot.json");
+        let loot_tables = serde_json::from_str::<LootTables>(data).unwrap();
+        assert!(!loot_tables.tables.is_empty());
+    }
+
+    /// Tables only apply within their depths, and empty entries produce nothing.
+    #[test]
+    fn roll() {
+        let mut rng = get_rng([1; 32]);
+        let source = Source::Creature(Species::Orc);
+        let loot_tables = LootTables {
+            tables: vec![
+                Table {
+                    source,
+                    min_depth: 0,
+                    max_depth: Some(2),
+                    rolls: 3,
+                    entries: vec![Entry { item: Some(ItemFactory::Rock), weight: 1 }],
+                },
+                Table {
+                    source,
+                    min_depth: 3,
+                    max_depth: None,
+                    rolls: 5,
+                    entries: vec![Entry { item: None, weight: 1 }],
+                },
+            ],
+        };
+        assert_eq!(vec![ItemFactory::Rock; 3], loot_tables.roll(source, 1, &mut rng));
+        assert!(loot_tables.roll(source, 9, &mut rng).is_empty());
+        assert!(loot_tables.roll(Source::Creature(Species::Troll), 1, &mut rng).is_empty());
+    }
+
+}
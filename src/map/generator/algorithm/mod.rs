@@ -1,10 +1,8 @@
+use super::Context;
 use super::MapGeneratorReturnType;
 use super::MapGeneratorType;
 use crate::entity;
 use entity::Entity;
-use crate::seed;
-use seed::SeedType;
-use seed::RngType;
 
 /// Empty...
 pub mod empty;
@@ -28,14 +26,14 @@ pub enum Algorithm {
 impl Algorithm {
 
     /// Generate the map.
-    pub fn generate_map(&self, seed: SeedType, rng: &mut RngType, width: i32, height: i32, level: i32, objects: &mut Vec<Entity>) -> MapGeneratorReturnType {
+    pub fn generate_map(&self, context: &mut Context<'_>, objects: &mut Vec<Entity>) -> MapGeneratorReturnType {
         use Algorithm::*;
         let generate_map: MapGeneratorType = match self {
             Empty => empty::generate_map,
             Simple => simple::generate_map,
             Random => random::generate_map,
         };
-        generate_map(seed, rng, width, height, level, objects)
+        generate_map(context, objects)
     }
 
 }
@@ -4,21 +4,37 @@ use crate::component;
 use component::position::Position;
 use crate::entity;
 use entity::Entity;
+use crate::item;
+use item::Factory as ItemFactory;
+use crate::loot;
+use loot::{LootTables, Source as LootSource};
+use loot::container::Factory as ContainerFactory;
 use crate::math;
 use math::geometry::rectangle::Rectangle;
+use crate::pack;
 use crate::seed;
 use seed::SeedType;
 use seed::RngType;
 use crate::species;
-use species::Factory as SpeciesFactory;
+use species::{Factory as SpeciesFactory, Species};
 use crate::tile;
 use tile::Tile;
+use super::super::Context;
 use super::super::MapGeneratorReturnType;
 use super::super::super::MapType;
 
 const ROOM_MAX_SIZE: i32 = 25;
 const ROOM_MIN_SIZE: i32 = 6;
 
+/// The fewest kobolds that join one in a swarm.
+const SWARM_MIN_SIZE: usize = 2;
+
+/// The most kobolds that join one in a swarm.
+const SWARM_MAX_SIZE: usize = 4;
+
+/// One room in this many has a puddle to drink from.
+const PUDDLE_ROOM_CHANCE: i32 = 2;
+
 /// Creates a room.
 fn create_room(room: Rectangle, map: &mut MapType) {
     for x in (room.x + 1)..room.x2() {
@@ -30,6 +46,18 @@ fn create_room(room: Rectangle, map: &mut MapType) {
     }
 }
 
+/// Leaves a puddle on the floor of some rooms.
+fn create_puddle(room: Rectangle, rng: &mut RngType, map: &mut MapType) {
+    if rng.gen_range(0, PUDDLE_ROOM_CHANCE) != 0 {
+        return;
+    }
+    let x = rng.gen_range(room.x + 1, room.x2());
+    let y = rng.gen_range(room.y + 1, room.y2());
+    if !map[x][y].blocks_movement {
+        map[x][y] = Tile::puddle();
+    }
+}
+
 fn create_h_tunnel(x1: i32, x2: i32, y: i32, map: &mut MapType) {
     for x in cmp::min(x1, x2)..(cmp::max(x1, x2) + 1) {
         map[x as usize][y as usize] = Tile::floor();
@@ -43,13 +71,14 @@ fn create_v_tunnel(y1: i32, y2: i32, x: i32, map: &mut MapType) {
 }
 
 fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32, entities: &mut Vec<Entity>) {
+    let first_id = entities.len();
     let num_monsters = rng.gen_range(0, room.width);
     for _ in 0..num_monsters {
         let x = rng.gen_range(room.x + 1, room.x2());
         let y = rng.gen_range(room.y + 1, room.y2());
         let monster_num = rng.gen_range(0, 20);
         let mut monster = if monster_num < 3 {
-            let mut orc = SpeciesFactory::Orc.create();
+            let mut orc = SpeciesFactory::Orc.create(rng);
             orc.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -58,7 +87,7 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             orc
         } else if monster_num < 4 {
-            let mut troll = SpeciesFactory::Troll.create();
+            let mut troll = SpeciesFactory::Troll.create(rng);
             troll.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -67,7 +96,7 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             troll
         } else if monster_num < 8 {
-            let mut goblin = SpeciesFactory::Goblin.create();
+            let mut goblin = SpeciesFactory::Goblin.create(rng);
             goblin.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -76,7 +105,7 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             goblin
         } else if monster_num < 10 {
-            let mut kobold = SpeciesFactory::Kobold.create();
+            let mut kobold = SpeciesFactory::Kobold.create(rng);
             kobold.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -85,7 +114,7 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             kobold
         } else if monster_num < 13 {
-            let mut chicken = SpeciesFactory::Chicken.create();
+            let mut chicken = SpeciesFactory::Chicken.create(rng);
             chicken.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -94,7 +123,7 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             chicken
         } else if monster_num < 17 {
-            let mut mushroom = SpeciesFactory::Mushroom.create();
+            let mut mushroom = SpeciesFactory::Mushroom.create(rng);
             mushroom.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -103,7 +132,7 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             mushroom
         } else {
-            let mut moss = SpeciesFactory::Moss.create();
+            let mut moss = SpeciesFactory::Moss.create(rng);
             moss.position = Some(Position {
                 w: seed,
                 x: x as i32,
@@ -112,13 +141,132 @@ fn place_objects(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32,
             });
             moss
         };
+        monster.advance_to_level(1 + level.max(0));
         monster.id = entities.len();
+        let is_swarm = monster.species == Some(Species::Kobold);
         entities.push(monster);
+        if is_swarm {
+            for _ in 0..rng.gen_range(SWARM_MIN_SIZE, SWARM_MAX_SIZE + 1) {
+                let mut kobold = SpeciesFactory::Kobold.create(rng);
+                kobold.position = Some(Position {
+                    w: seed,
+                    x: rng.gen_range(x.max(room.x + 2) - 1, (x + 2).min(room.x2())) as i32,
+                    y: rng.gen_range(y.max(room.y + 2) - 1, (y + 2).min(room.y2())) as i32,
+                    z: level,
+                });
+                kobold.advance_to_level(1 + level.max(0));
+                kobold.id = entities.len();
+                entities.push(kobold);
+            }
+        }
+    }
+    for monster in entities[first_id..].iter_mut() {
+        if let Some(needs) = monster.needs.as_mut() {
+            needs.scatter(rng);
+        }
+    }
+    // Creatures that form packs band together with their kind in the same room.
+    let mut pack_species: Vec<Species> = vec![];
+    for species in entities[first_id..].iter().filter_map(|entity| entity.species) {
+        if species.forms_packs() && !pack_species.contains(&species) {
+            pack_species.push(species);
+        }
+    }
+    for species in pack_species {
+        let ids = (first_id..entities.len())
+            .filter(|&id| entities[id].species == Some(species))
+            .collect::<Vec<usize>>();
+        if ids.len() >= 2 {
+            pack::form(&ids, entities);
+        }
+    }
+}
+
+/// Places items lying on the floor of a room.
+fn place_items(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32, entities: &mut Vec<Entity>) {
+    let num_items = rng.gen_range(0, 3);
+    for _ in 0..num_items {
+        let x = rng.gen_range(room.x + 1, room.x2());
+        let y = rng.gen_range(room.y + 1, room.y2());
+        let item_num = rng.gen_range(0, 30);
+        let mut item = if item_num < 8 {
+            ItemFactory::Rock.create()
+        } else if item_num < 12 {
+            ItemFactory::Bone.create()
+        } else if item_num < 14 {
+            ItemFactory::Dagger.create()
+        } else if item_num < 16 {
+            ItemFactory::Torch.create()
+        } else if item_num < 17 {
+            ItemFactory::LeatherArmor.create()
+        } else if item_num < 18 {
+            ItemFactory::Helmet.create()
+        } else if item_num < 20 {
+            ItemFactory::PotionOfHealing.create()
+        } else if item_num < 21 {
+            ItemFactory::PotionOfLight.create()
+        } else if item_num < 22 {
+            ItemFactory::ScrollOfTeleportation.create()
+        } else if item_num < 23 {
+            ItemFactory::ScrollOfMagicMapping.create()
+        } else if item_num < 24 {
+            ItemFactory::ScrollOfMoss.create()
+        } else if item_num < 27 {
+            ItemFactory::Ration.create()
+        } else {
+            ItemFactory::Bread.create()
+        };
+        if let Some(light_source) = item.light_source {
+            item.light_source = Some(light_source.with_seed(rng.gen()));
+        }
+        item.position = Some(Position {
+            w: seed,
+            x: x as i32,
+            y: y as i32,
+            z: level,
+        });
+        item.id = entities.len();
+        entities.push(item);
+    }
+}
+
+/// Places a container in a room, filled from the loot tables.
+fn place_containers(room: Rectangle, seed: SeedType, rng: &mut RngType, level: i32, loot_tables: &LootTables, entities: &mut Vec<Entity>) {
+    let container_num = rng.gen_range(0, 12);
+    let container_factory = if container_num < 3 {
+        ContainerFactory::Chest
+    } else if container_num < 5 {
+        ContainerFactory::Sack
+    } else {
+        return;
+    };
+    let x = rng.gen_range(room.x + 1, room.x2());
+    let y = rng.gen_range(room.y + 1, room.y2());
+    let mut container = container_factory.create();
+    container.position = Some(Position {
+        w: seed,
+        x: x as i32,
+        y: y as i32,
+        z: level,
+    });
+    let container_id = entities.len();
+    container.id = container_id;
+    entities.push(container);
+    for item_factory in loot_tables.roll(LootSource::Container(container_factory.get_kind()), level, rng) {
+        let mut item = item_factory.create();
+        let item_id = entities.len();
+        item.id = item_id;
+        entities.push(item);
+        if let Some(inventory) = entities[container_id].inventory.as_mut() {
+            inventory.add(item_id);
+        }
     }
 }
 
 /// Generate the map.
-pub fn generate_map(seed: SeedType, rng: &mut RngType, width: i32, height: i32, level: i32, entities: &mut Vec<Entity>) -> MapGeneratorReturnType {
+pub fn generate_map(context: &mut Context<'_>, entities: &mut Vec<Entity>) -> MapGeneratorReturnType {
+    let Context { seed, width, height, level, loot_tables, .. } = *context;
+    let rng = &mut *context.rng;
     let mut map = vec![vec![Tile::wall(); height as usize]; width as usize];
     let mut rooms = vec![];
     let mut starting_position = Position::new(seed, width / 2, height / 2, level);
@@ -133,8 +281,11 @@ pub fn generate_map(seed: SeedType, rng: &mut RngType, width: i32, height: i32,
             .any(|other_room| new_room.overlaps(other_room));
         if !failed {
             create_room(new_room, &mut map);
+            create_puddle(new_room, rng, &mut map);
             if !rooms.is_empty() {
                 place_objects(new_room, seed, rng, level, entities);
+                place_items(new_room, seed, rng, level, entities);
+                place_containers(new_room, seed, rng, level, loot_tables, entities);
             }
             let (new_x, new_y) = new_room.center().as_tuple();
             if rooms.is_empty() {
@@ -3,6 +3,8 @@ use component::position::Position;
 use super::MapType;
 use crate::entity;
 use entity::Entity;
+use crate::loot;
+use loot::LootTables;
 use crate::seed;
 use seed::SeedType;
 use seed::RngType;
@@ -14,4 +16,21 @@ pub mod algorithm;
 pub type MapGeneratorReturnType = (MapType, Position);
 
 /// The type of function of a map generator.
-pub type MapGeneratorType = fn(SeedType, &mut RngType, i32, i32, i32, &mut Vec<Entity>) -> MapGeneratorReturnType;
+pub type MapGeneratorType = fn(&mut Context<'_>, &mut Vec<Entity>) -> MapGeneratorReturnType;
+
+/// The inputs to a map generator.
+#[derive(Debug)]
+pub struct Context<'a> {
+    /// The seed of the world being generated.
+    pub seed: SeedType,
+    /// The source of randomness for the level.
+    pub rng: &'a mut RngType,
+    /// The width of the map.
+    pub width: i32,
+    /// The height of the map.
+    pub height: i32,
+    /// The depth of the level.
+    pub level: i32,
+    /// The tables from which containers are filled.
+    pub loot_tables: &'a LootTables,
+}
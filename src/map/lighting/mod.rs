@@ -0,0 +1,219 @@
+use std::cmp;
+use std::mem;
+use bear_lib_terminal::Color;
+use tcod::map::FovAlgorithm;
+use crate::component;
+use component::light_source::LightSource;
+use crate::game;
+use game::Game;
+
+/// The tiles lit by a single light source.
+#[derive(Clone, Debug)]
+struct LitArea {
+    /// The light source.
+    light_source: LightSource,
+    /// The x-coordinate of the light source.
+    x: i32,
+    /// The y-coordinate of the light source.
+    y: i32,
+    /// The tiles that the light source can reach.
+    cells: Vec<(i32, i32)>,
+}
+
+/// The light falling on each tile of a map, computed once per turn.
+#[derive(Clone, Debug)]
+pub struct LightMap {
+    /// The width of the map.
+    pub width: usize,
+    /// The height of the map.
+    pub height: usize,
+    /// The accumulated (unclamped) red, green, and blue light at each tile.
+    colors: Vec<Vec<(i32, i32, i32)>>,
+    /// The accumulated intensity of light at each tile.
+    intensities: Vec<Vec<i32>>,
+    /// The areas lit by each light source; occlusion is only computed once per turn.
+    areas: Vec<LitArea>,
+}
+
+/// The light falling on each tile of a map, computed once per turn.
+impl LightMap {
+
+    /// Constructor.
+    pub fn new(width: usize, height: usize) -> Self {
+        trace!("Entering LightMap::new().");
+        LightMap {
+            width,
+            height,
+            colors: vec![vec![(0, 0, 0); height]; width],
+            intensities: vec![vec![0; height]; width],
+            areas: vec![],
+        }
+    }
+
+    /// Compute the light map from every light source in the game.
+    ///
+    /// Each light source casts light only onto tiles it can "see", so walls
+    /// and opaque entities occlude it.  Animated lights are sampled at the
+    /// current turn.
+    pub fn compute(game: &Game) -> Self {
+        trace!("Entering LightMap::compute().");
+        let mut light_map = LightMap::new(game.map.width, game.map.height);
+        let mut fov_map = game.get_fov_map();
+        for entity in &game.entities {
+            if let (Some(light_source), Some(position)) = (entity.light_source, entity.position) {
+                let radius = light_source.radius;
+                fov_map.compute_fov(position.x, position.y, radius, true, FovAlgorithm::Basic);
+                let mut cells = vec![];
+                for y in (position.y - radius)..=(position.y + radius) {
+                    for x in (position.x - radius)..=(position.x + radius) {
+                        if light_map.is_in_bounds(x, y) && fov_map.is_in_fov(x, y) {
+                            cells.push((x, y));
+                        }
+                    }
+                }
+                light_map.areas.push(LitArea {
+                    light_source,
+                    x: position.x,
+                    y: position.y,
+                    cells,
+                });
+            }
+        }
+        light_map.accumulate(game.turns as f64);
+        trace!("Exiting LightMap::compute().");
+        light_map
+    }
+
+    /// Recompute the accumulated light, sampling animated lights at the specified time.
+    pub fn accumulate(&mut self, time: f64) {
+        trace!("Entering LightMap::accumulate().");
+        self.colors = vec![vec![(0, 0, 0); self.height]; self.width];
+        self.intensities = vec![vec![0; self.height]; self.width];
+        let areas = mem::take(&mut self.areas);
+        for area in &areas {
+            self.add_area(area, time);
+        }
+        self.areas = areas;
+        trace!("Exiting LightMap::accumulate().");
+    }
+
+    /// A copy of this light map with animated lights sampled at the specified time.
+    ///
+    /// This is cheap enough to call every frame, since occlusion isn't recomputed.
+    pub fn animated(&self, time: f64) -> LightMap {
+        let mut light_map = LightMap::new(self.width, self.height);
+        for area in &self.areas {
+            light_map.add_area(area, time);
+        }
+        light_map
+    }
+
+    /// Add the light from a lit area at the specified time.
+    fn add_area(&mut self, area: &LitArea, time: f64) {
+        let light_source = area.light_source.at_time(time);
+        for &(x2, y2) in &area.cells {
+            self.add_light_at(&light_source, area.x, area.y, x2, y2);
+        }
+    }
+
+    /// Indicates whether a pair of coordinates are in bounds of this light map.
+    pub fn is_in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// Add the light cast onto (x2, y2) by a light source located at (x, y).
+    pub fn add_light_at(&mut self, light_source: &LightSource, x: i32, y: i32, x2: i32, y2: i32) {
+        if !self.is_in_bounds(x2, y2) {
+            return;
+        }
+        let intensity = light_source.intensity_at(x, y, x2, y2);
+        if intensity <= 0 {
+            return;
+        }
+        let color = light_source.color;
+        let (red, green, blue) = &mut self.colors[x2 as usize][y2 as usize];
+        *red += color.red as i32 * intensity / 512;
+        *green += color.green as i32 * intensity / 512;
+        *blue += color.blue as i32 * intensity / 512;
+        self.intensities[x2 as usize][y2 as usize] += intensity;
+    }
+
+    /// The intensity of light at the specified location.
+    pub fn get_intensity_at(&self, x: i32, y: i32) -> i32 {
+        if !self.is_in_bounds(x, y) {
+            return 0;
+        }
+        self.intensities[x as usize][y as usize]
+    }
+
+    /// The color of light at the specified location.
+    pub fn get_color_at(&self, x: i32, y: i32) -> Color {
+        if !self.is_in_bounds(x, y) {
+            return Color::from_rgb(0, 0, 0);
+        }
+        let (red, green, blue) = self.colors[x as usize][y as usize];
+        Color::from_rgb(
+            cmp::min(red, 255) as u8,
+            cmp::min(green, 255) as u8,
+            cmp::min(blue, 255) as u8,
+        )
+    }
+
+    /// Add the light at the specified location to a color.
+    pub fn transform_color_at(&self, color: Color, x: i32, y: i32) -> Color {
+        if !self.is_in_bounds(x, y) {
+            return color;
+        }
+        let (red, green, blue) = self.colors[x as usize][y as usize];
+        Color::from_rgb(
+            cmp::min(color.red as i32 + red, 255) as u8,
+            cmp::min(color.green as i32 + green, 255) as u8,
+            cmp::min(color.blue as i32 + blue, 255) as u8,
+        )
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Light falls off with distance from the source.
+    #[test]
+    fn add_light_at() {
+        let light_source = LightSource::new(Color::from_rgb(255, 0, 0), 4, 128);
+        let mut light_map = LightMap::new(10, 10);
+        for x in 0..10 {
+            light_map.add_light_at(&light_source, 2, 2, x, 2);
+        }
+        assert_eq!(128, light_map.get_intensity_at(2, 2));
+        assert!(light_map.get_intensity_at(3, 2) < light_map.get_intensity_at(2, 2));
+        assert_eq!(0, light_map.get_intensity_at(7, 2));
+        assert_eq!(0, light_map.get_color_at(2, 2).green);
+    }
+
+    /// Light from several sources is additive.
+    #[test]
+    fn additive() {
+        let red = LightSource::new(Color::from_rgb(255, 0, 0), 4, 128);
+        let blue = LightSource::new(Color::from_rgb(0, 0, 255), 4, 128);
+        let mut light_map = LightMap::new(10, 10);
+        light_map.add_light_at(&red, 2, 2, 2, 2);
+        light_map.add_light_at(&blue, 2, 2, 2, 2);
+        assert_eq!(256, light_map.get_intensity_at(2, 2));
+        let color = light_map.transform_color_at(Color::from_rgb(16, 16, 16), 2, 2);
+        assert_eq!(16 + 63, color.red);
+        assert_eq!(16, color.green);
+        assert_eq!(16 + 63, color.blue);
+    }
+
+    /// Out-of-bounds queries are dark.
+    #[test]
+    fn out_of_bounds() {
+        let light_map = LightMap::new(10, 10);
+        assert_eq!(0, light_map.get_intensity_at(-1, 3));
+        assert_eq!(0, light_map.get_intensity_at(10, 3));
+    }
+
+}
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
-use std::cmp;
 use std::fmt;
+use rand::Rng;
 use bear_lib_terminal::terminal as blt;
 use ::ntree::NTree;
 use crate::component;
@@ -11,6 +11,8 @@ use crate::entity;
 use entity::Entity;
 use crate::game;
 use game::Game;
+use crate::loot;
+use loot::LootTables;
 use crate::seed;
 use seed::SeedType;
 use seed::RngType;
@@ -25,6 +27,10 @@ use ui::Ui;
 /// The map generators.
 pub mod generator;
 
+/// The per-turn light map.
+pub mod lighting;
+use lighting::LightMap;
+
 /// The quad-tree for spatial trees.
 pub mod quadtree;
 use quadtree::QuadTreeRegion;
@@ -46,6 +52,8 @@ pub struct Map {
     pub width: usize,
     /// The spatial hash map.
     pub spatial_hash: HashMap<(usize, usize), HashSet<usize>>,
+    /// The spatial hash map of items lying on the ground.
+    pub item_hash: HashMap<(usize, usize), HashSet<usize>>,
 }
 
 /// The map object.
@@ -56,9 +64,11 @@ impl Map {
         let height = map[0].len();
         let width = map.len();
         let mut spatial_hash = HashMap::new();
+        let mut item_hash = HashMap::new();
         for y in 0..height {
             for x in 0..width {
                 spatial_hash.insert((x, y), HashSet::new());
+                item_hash.insert((x, y), HashSet::new());
             }
         }
         Map {
@@ -66,6 +76,7 @@ impl Map {
             height: height,
             width: width,
             spatial_hash: spatial_hash,
+            item_hash,
         }
     }
 
@@ -91,35 +102,7 @@ impl Map {
 
     /// Indicates whether a pair of coordinates are in bounds of this map.
     pub fn is_in_bounds(&self, x: usize, y: usize) -> bool {
-        (x < self.width - 1 && y < self.height - 1)
-    }
-
-    /// Get a light source quadtree for the map.
-    pub fn get_ls_tree(&self, game: &Game) -> QuadTreeType {
-        let mut ls_tree: QuadTreeType = NTree::new(QuadTreeRegion {
-            x: 0,
-            y: 0,
-            width: self.width as i32,
-            height: self.height as i32,
-        }, 16);
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let ids = self.get_entities(x, y)
-                    .unwrap_or(HashSet::new())
-                    .iter()
-                    .filter(|&id| game.entities[*id].light_source.is_some())
-                    .map(|&id| id)
-                    .collect::<Vec<usize>>();
-                for id in ids {
-                    ls_tree.insert(QuadTreePoint {
-                        id: id,
-                        x: x as i32,
-                        y: y as i32,
-                    });
-                }
-            }
-        }
-        ls_tree
+        x < self.width - 1 && y < self.height - 1
     }
 
     /// Get a quadtree for all entities of a specific species for this map.
@@ -152,26 +135,26 @@ impl Map {
     }
 
     /// Render this map, taking into account the provided field of view.
-    pub fn draw(&self, _ui: &Ui, fov: &FieldOfView, game: &Game) {
+    pub fn draw(&self, ui: &Ui, fov: &FieldOfView, game: &Game) {
         trace!("Entering Map::draw().");
         let fov_map = fov.map.lock().unwrap().clone();
-        let ls_tree = self.get_ls_tree(game);
-        let radius: i32 = 12;
+        let light_map = game.light_map.animated(ui.get_time());
         for y in 0..self.height {
             for x in 0..self.width {
                 if fov_map.is_in_fov(x as i32, y as i32) {
-                    let region = QuadTreeRegion {
-                        x: cmp::max(x as i32 - radius, 0),
-                        y: cmp::max(y as i32 - radius, 0),
-                        width: cmp::min(x  as i32 + radius, self.width as i32 - 1),
-                        height: cmp::min(y as i32 + radius, self.height as i32 - 1),
-                    };
-                    let ls_vector = ls_tree
-                        .range_query(&region)
-                        .map(|x| x.id)
-                        .collect::<Vec<usize>>();
                     let renderable = &self.map[x][y].renderable;
-                    self.draw_tile_renderable(x, y, &renderable, game, &ls_vector);
+                    self.draw_tile_renderable(x, y, renderable, Some(&light_map));
+                    for id in self.get_items(x, y)
+                        .unwrap_or_default()
+                        .iter() {
+                        if !game.can_see_entity(game.player_id, *id) {
+                            continue;
+                        }
+                        if let Some(renderable) = &game.entities[*id].renderable {
+                            self.draw_entity_renderable(x, y, renderable);
+                            break;
+                        }
+                    }
                     let mut occupant_found: bool = false;
                     for id in self.get_entities(x, y)
                         .unwrap_or(HashSet::new())
@@ -181,23 +164,25 @@ impl Map {
                             break;
                         }
                         let entity = &game.entities[*id];
+                        if !game.can_see_entity(game.player_id, *id) {
+                            continue;
+                        }
                         occupant_found = entity.blocks_movement;
                         if let Some(renderable) = &entity.renderable {
                             self.draw_entity_renderable(x, y, &renderable);
                         }
                     }
                 } else if self.is_in_bounds(x, y) && fov.explored_map[x][y] {
-                    let ls_vector = vec![];
                     let renderable = &self.map[x][y].renderable;
-                    self.draw_tile_renderable(x, y, &renderable, game, &ls_vector);
+                    self.draw_tile_renderable(x, y, renderable, None);
                 }
             }
         }
         trace!("Exiting Map::draw().");
     }
 
-    /// Render this object at the specified position.
-    pub fn draw_tile_renderable(&self, x: usize, y: usize, renderable: &Renderable, game: &Game, ls_vector: &Vec<usize>) {
+    /// Render this object at the specified position, optionally lit by the light map.
+    pub fn draw_tile_renderable(&self, x: usize, y: usize, renderable: &Renderable, light_map: Option<&LightMap>) {
         trace!("Entering Renderable::draw_tile_renderable().");
         use bear_lib_terminal::geometry::Point;
         let point = Point::new(x as i32, y as i32);
@@ -213,18 +198,8 @@ impl Map {
         if let Some(char) = renderable.char {
             the_char = char;
         }
-        for id in ls_vector {
-            let entity = &game.entities[*id];
-            if let Some(position) = entity.position {
-                if let Some(light_source) = entity.light_source {
-                    if let Some(fov) = &entity.field_of_view {
-                        let fov_map = fov.map.lock().unwrap();
-                        if fov_map.is_in_fov(x as i32, y as i32) {
-                            bg_color = light_source.transform_color_at(bg_color, position.x, position.y, x as i32, y as i32);
-                        }
-                    }
-                }
-            }
+        if let Some(light_map) = light_map {
+            bg_color = light_map.transform_color_at(bg_color, x as i32, y as i32);
         }
         blt::with_colors(fg_color, bg_color, || blt::put_xy(x as i32, y as i32, the_char));
         trace!("Exiting Renderable::draw_entity_renderable().");
@@ -261,6 +236,13 @@ impl Map {
         self.get_tile(position.x as usize, position.y as usize)
     }
 
+    /// Replaces the tile at the specified position.
+    ///
+    /// Callers are responsible for notifying fields of view; see `Game::notify_map_changed()`.
+    pub fn set_tile(&mut self, x: usize, y: usize, tile: Tile) {
+        self.map[x][y] = tile;
+    }
+
     /// Removes an entity at the specified position.
     pub fn remove_entity(&mut self, id: usize, x: usize, y: usize) {
         if let Some(set) = self.spatial_hash.get_mut(&(x, y)) {
@@ -289,6 +271,28 @@ impl Map {
         None
     }
 
+    /// Removes an item lying at the specified position.
+    pub fn remove_item(&mut self, id: usize, x: usize, y: usize) {
+        if let Some(set) = self.item_hash.get_mut(&(x, y)) {
+            set.remove(&id);
+        }
+    }
+
+    /// Adds an item lying at the specified position.
+    pub fn insert_item(&mut self, id: usize, x: usize, y: usize) {
+        if let Some(set) = self.item_hash.get_mut(&(x, y)) {
+            set.insert(id);
+        }
+    }
+
+    /// Gets the IDs of items lying at a specific location.
+    pub fn get_items(&self, x: usize, y: usize) -> Option<HashSet<usize>> {
+        if let Some(hashset) = self.item_hash.get(&(x, y)) {
+            return Some(hashset.clone());
+        }
+        None
+    }
+
     /// Gets entity IDs at a specific location.
     pub fn get_entities_around(&self, x: usize, y: usize) -> Vec<usize> {
         let mut result: Vec<usize> = vec![];
@@ -319,14 +323,39 @@ impl fmt::Debug for Map {
 }
 
 /// Get a new map.
-pub fn get_map(seed: SeedType, rng: &mut RngType, width: i32, height: i32, level: i32, entities: &mut Vec<Entity>) -> (Map, Position) {
-    let (inner_map, position) = generator::algorithm::Algorithm::Simple.generate_map(seed, rng, width, height, level, entities);
+pub fn get_map(seed: SeedType, rng: &mut RngType, width: i32, height: i32, level: i32, loot_tables: &LootTables, entities: &mut Vec<Entity>) -> (Map, Position) {
+    let mut context = generator::Context {
+        seed,
+        rng,
+        width,
+        height,
+        level,
+        loot_tables,
+    };
+    let (inner_map, position) = generator::algorithm::Algorithm::Simple.generate_map(&mut context, entities);
     let mut map = Map::new(inner_map);
     for entity in entities {
-        entity.field_of_view = Some(FieldOfView::new(map.get_fov(), 10));
+        if let Some(light_source) = entity.light_source.as_mut() {
+            light_source.seed = rng.gen();
+        }
+        if entity.item.is_some() {
+            if let Some(position) = &entity.position {
+                map.insert_item(entity.id, position.x as usize, position.y as usize);
+            }
+            continue;
+        }
+        if entity.container.is_some() {
+            if let Some(position) = &entity.position {
+                map.insert_entity(entity.id, position.x as usize, position.y as usize);
+            }
+            continue;
+        }
+        let mut field_of_view = FieldOfView::new(map.get_fov(), 10);
         if let Some(position) = &entity.position {
+            field_of_view.update(position.x, position.y);
             map.insert_entity(entity.id, position.x as usize, position.y as usize);
         }
+        entity.field_of_view = Some(field_of_view);
     }
     (map, position)
 }
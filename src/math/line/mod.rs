@@ -0,0 +1,80 @@
+/// The cells on a Bresenham line from (x0, y0) to (x1, y1), inclusive.
+pub fn get_line(x0: i32, y0: i32, x1: i32, y1: i32) -> Vec<(i32, i32)> {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    let (mut x, mut y) = (x0, y0);
+    let mut result = vec![(x, y)];
+    while x != x1 || y != y1 {
+        let error2 = 2 * error;
+        if error2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if error2 <= dx {
+            error += dx;
+            y += sy;
+        }
+        result.push((x, y));
+    }
+    result
+}
+
+/// The cells on a ray from (x0, y0) through (x1, y1), continuing past it.
+///
+/// The ray excludes the origin and contains exactly `length` cells.
+pub fn get_ray(x0: i32, y0: i32, x1: i32, y1: i32, length: usize) -> Vec<(i32, i32)> {
+    if (x0, y0) == (x1, y1) || length == 0 {
+        return vec![];
+    }
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let steps = dx.abs().max(dy.abs());
+    let scale = (length as i32 + steps - 1) / steps;
+    get_line(x0, y0, x0 + dx * scale, y0 + dy * scale)
+        .into_iter()
+        .skip(1)
+        .take(length)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Lines include both endpoints, and step one cell at a time.
+    #[test]
+    fn get_line_endpoints() {
+        let line = get_line(1, 1, 6, 3);
+        assert_eq!(Some(&(1, 1)), line.first());
+        assert_eq!(Some(&(6, 3)), line.last());
+        assert_eq!(6, line.len());
+        for pair in line.windows(2) {
+            let (x0, y0) = pair[0];
+            let (x1, y1) = pair[1];
+            assert!((x1 - x0).abs() <= 1 && (y1 - y0).abs() <= 1);
+        }
+    }
+
+    /// Orthogonal, diagonal, and degenerate lines.
+    #[test]
+    fn get_line_simple() {
+        assert_eq!(vec![(0, 0), (1, 0), (2, 0)], get_line(0, 0, 2, 0));
+        assert_eq!(vec![(2, 2), (1, 1), (0, 0)], get_line(2, 2, 0, 0));
+        assert_eq!(vec![(0, 0), (0, -1)], get_line(0, 0, 0, -1));
+        assert_eq!(vec![(3, 3)], get_line(3, 3, 3, 3));
+    }
+
+    /// Rays continue past their target.
+    #[test]
+    fn get_ray_continues() {
+        let ray = get_ray(0, 0, 2, 1, 6);
+        assert_eq!(6, ray.len());
+        assert!(ray.contains(&(2, 1)));
+        assert_eq!(Some(&(6, 3)), ray.last());
+        assert!(get_ray(1, 1, 1, 1, 5).is_empty());
+    }
+
+}
@@ -1,2 +1,6 @@
 /// Geometry.
 pub mod geometry;
+/// Lines.
+pub mod line;
+/// Noise.
+pub mod noise;
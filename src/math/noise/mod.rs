@@ -0,0 +1,62 @@
+/// Hash an integer into a pseudorandom value, given a seed.
+pub fn hash(seed: u32, n: i64) -> u32 {
+    let mut x = (n as u64) ^ (u64::from(seed) << 32) ^ 0x9E37_79B9_7F4A_7C15;
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    x as u32
+}
+
+/// Hash a pair of coordinates into a pseudorandom value, given a seed.
+pub fn hash_2d(seed: u32, x: i32, y: i32) -> u32 {
+    hash(hash(seed, i64::from(x)), i64::from(y))
+}
+
+/// A pseudorandom value in [-1, 1] for an integer lattice point.
+fn lattice(seed: u32, n: i64) -> f64 {
+    f64::from(hash(seed, n)) / f64::from(u32::MAX) * 2.0 - 1.0
+}
+
+/// Smooth one-dimensional value noise in [-1, 1].
+pub fn noise_1d(seed: u32, t: f64) -> f64 {
+    let t0 = t.floor();
+    let i = t0 as i64;
+    let f = t - t0;
+    let s = f * f * (3.0 - 2.0 * f);
+    let a = lattice(seed, i);
+    let b = lattice(seed, i + 1);
+    a + (b - a) * s
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// The same seed and input always produce the same output.
+    #[test]
+    fn deterministic() {
+        assert_eq!(hash(7, 12), hash(7, 12));
+        assert_eq!(noise_1d(7, 3.25), noise_1d(7, 3.25));
+        assert_ne!(hash(7, 12), hash(8, 12));
+    }
+
+    /// Noise stays within its range.
+    #[test]
+    fn range() {
+        for i in 0..1000 {
+            let value = noise_1d(42, f64::from(i) * 0.37);
+            assert!(value >= -1.0 && value <= 1.0);
+        }
+    }
+
+    /// Noise is continuous.
+    #[test]
+    fn continuous() {
+        for i in 0..1000 {
+            let t = f64::from(i) * 0.01;
+            assert!((noise_1d(42, t) - noise_1d(42, t + 0.001)).abs() < 0.01);
+        }
+    }
+
+}
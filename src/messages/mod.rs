@@ -0,0 +1,64 @@
+use bear_lib_terminal::Color;
+
+/// The maximum number of messages retained in the log.
+const MAX_MESSAGES: usize = 100;
+
+/// A message for the player.
+#[derive(Clone, Debug)]
+pub struct Message {
+    /// The text of the message.
+    pub text: String,
+    /// The color in which the message is displayed.
+    pub color: Color,
+    /// The turn on which the message was added.
+    pub turn: usize,
+}
+
+/// The message log.
+#[derive(Clone, Debug)]
+pub struct Messages {
+    /// The messages, oldest first.
+    pub entries: Vec<Message>,
+}
+
+/// The message log.
+impl Messages {
+
+    /// Constructor.
+    pub fn new() -> Self {
+        Messages {
+            entries: vec![],
+        }
+    }
+
+    /// Add a message, discarding the oldest if the log is full.
+    pub fn add(&mut self, text: String, color: Color, turn: usize) {
+        trace!("Entering Messages::add().");
+        debug!("Message: {}", text);
+        self.entries.push(Message {
+            text,
+            color,
+            turn,
+        });
+        if self.entries.len() > MAX_MESSAGES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// The most recent messages, oldest first.
+    pub fn get_recent(&self, count: usize) -> &[Message] {
+        let start = self.entries.len().saturating_sub(count);
+        &self.entries[start..]
+    }
+
+}
+
+/// Creates a default instance.
+impl Default for Messages {
+
+    /// Creates a default instance.
+    fn default() -> Self {
+        Messages::new()
+    }
+
+}
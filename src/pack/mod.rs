@@ -0,0 +1,130 @@
+use crate::component;
+use component::group::Group;
+use crate::entity;
+use entity::Entity;
+use crate::game;
+use game::Game;
+
+/// The distance within which packmates hear a call for help.
+pub const HEARING_DISTANCE: f32 = 12.0;
+
+/// Band the entities together into a pack, led by the first.
+pub fn form(ids: &[usize], entities: &mut [Entity]) {
+    let leader_id = match ids.first() {
+        Some(&leader_id) => leader_id,
+        None => return,
+    };
+    for (slot, &id) in ids.iter().enumerate() {
+        entities[id].group = Some(Group::new(leader_id, slot));
+    }
+}
+
+/// The living members of a pack, in formation order.
+pub fn get_members(leader_id: usize, game: &Game) -> Vec<usize> {
+    let mut members = game.entities
+        .iter()
+        .filter(|entity| entity.body.is_some())
+        .filter_map(|entity| match entity.group {
+            Some(group) if group.leader_id == leader_id => Some((group.slot, entity.id)),
+            _ => None,
+        })
+        .collect::<Vec<(usize, usize)>>();
+    members.sort_unstable();
+    members
+        .into_iter()
+        .map(|(_, id)| id)
+        .collect()
+}
+
+/// The entity's living packmates, not counting itself.
+pub fn get_packmates(id: usize, game: &Game) -> Vec<usize> {
+    match game.entities[id].group {
+        Some(group) => get_members(group.leader_id, game)
+            .into_iter()
+            .filter(|&member_id| member_id != id)
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Packmates within earshot rally to the caller against the threat.
+///
+/// Each remembers where the threat is, and takes it as its target unless it
+/// already has one.
+pub fn answer(caller_id: usize, threat_id: usize, game: &mut Game) {
+    trace!("Entering pack::answer() for caller {} and threat {}.", caller_id, threat_id);
+    let (caller_position, threat_position) = match (game.entities[caller_id].position, game.entities[threat_id].position) {
+        (Some(caller_position), Some(threat_position)) => (caller_position, threat_position),
+        _ => return,
+    };
+    let listener_ids = get_packmates(caller_id, game)
+        .into_iter()
+        .filter(|&member_id| {
+            let member = &game.entities[member_id];
+            member.would_attack(&game.entities[threat_id])
+                && member.position.is_some_and(|position| position.distance_to(&caller_position) <= HEARING_DISTANCE)
+        })
+        .collect::<Vec<usize>>();
+    let turn = game.turns;
+    for listener_id in listener_ids {
+        let listener = &mut game.entities[listener_id];
+        if let Some(agent) = listener.agent.as_mut() {
+            let blackboard = &mut agent.blackboard;
+            blackboard.memory.remember(threat_id, threat_position, turn);
+            if blackboard.target_id.is_none() {
+                debug!("Entity {} answers the call of entity {}.", listener.name, caller_id);
+                blackboard.set_target(threat_id, Some(threat_position));
+            }
+        }
+    }
+    trace!("Exiting pack::answer().");
+}
+
+/// Remove the entity from its pack, closing ranks behind it.
+///
+/// If the entity led the pack, the next in line takes over; a lone survivor
+/// is no longer a pack at all.
+pub fn leave(id: usize, game: &mut Game) {
+    let group = match game.entities[id].group.take() {
+        Some(group) => group,
+        None => return,
+    };
+    let member_ids = get_members(group.leader_id, game);
+    if member_ids.len() < 2 {
+        for member_id in member_ids {
+            game.entities[member_id].group = None;
+        }
+        return;
+    }
+    form(&member_ids, &mut game.entities);
+}
+
+/// Choose where to stand to surround a target.
+///
+/// Of the free cells around the target, take the nearest one that no packmate
+/// is closer to, so that the pack spreads out rather than queueing up.
+pub fn choose_flank(free_cells: &[(i32, i32)], own: (i32, i32), packmates: &[(i32, i32)]) -> Option<(i32, i32)> {
+    let get_distance = |from: (i32, i32), to: (i32, i32)| (from.0 - to.0).abs().max((from.1 - to.1).abs());
+    free_cells
+        .iter()
+        .copied()
+        .filter(|&cell| packmates.iter().all(|&packmate| get_distance(packmate, cell) >= get_distance(own, cell)))
+        .min_by_key(|&cell| get_distance(own, cell))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Packmates leave each other the cells they are closest to.
+    #[test]
+    fn flank() {
+        let free_cells = [(6, 6), (4, 6)];
+        assert_eq!(choose_flank(&free_cells, (5, 8), &[]), Some((6, 6)));
+        assert_eq!(choose_flank(&free_cells, (5, 8), &[(7, 7)]), Some((4, 6)));
+        assert_eq!(choose_flank(&free_cells, (5, 8), &[(7, 7), (3, 7)]), None);
+        assert_eq!(choose_flank(&[], (5, 8), &[]), None);
+    }
+
+}
@@ -0,0 +1,165 @@
+use std::cmp;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use super::{ORTHOGONAL_COST, DIAGONAL_COST};
+
+/// A point on the open list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct OpenNode {
+    /// The estimated total cost of a path through this point.
+    estimate: i32,
+    /// The point.
+    point: (i32, i32),
+}
+
+/// Reversed, so that the binary heap pops the cheapest node first.
+impl Ord for OpenNode {
+    fn cmp(&self, other: &OpenNode) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+            .then_with(|| self.point.cmp(&other.point))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &OpenNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The octile distance between two points, in movement cost units.
+pub fn heuristic(start: (i32, i32), goal: (i32, i32)) -> i32 {
+    let dx = (goal.0 - start.0).abs();
+    let dy = (goal.1 - start.1).abs();
+    ORTHOGONAL_COST * (dx + dy) + (DIAGONAL_COST - 2 * ORTHOGONAL_COST) * cmp::min(dx, dy)
+}
+
+/// Find the cheapest 8-way path from start to goal.
+///
+/// `get_cost` returns the cost of an orthogonal step into a location (at
+/// least `ORTHOGONAL_COST`), or `None` if the location is impassable.  The
+/// search gives up after expanding `max_nodes` points.  The returned path
+/// excludes the start and includes the goal.
+pub fn find_path<F>(start: (i32, i32), goal: (i32, i32), max_nodes: usize, get_cost: F) -> Option<Vec<(i32, i32)>>
+    where F: Fn(i32, i32) -> Option<i32> {
+    trace!("Entering astar::find_path().");
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut best_cost: HashMap<(i32, i32), i32> = HashMap::new();
+    best_cost.insert(start, 0);
+    open.push(OpenNode {
+        estimate: heuristic(start, goal),
+        point: start,
+    });
+    let mut expanded = 0;
+    while let Some(OpenNode { estimate, point }) = open.pop() {
+        if point == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while let Some(&previous) = came_from.get(&current) {
+                if previous == start {
+                    break;
+                }
+                path.push(previous);
+                current = previous;
+            }
+            path.reverse();
+            trace!("Exiting astar::find_path().");
+            return Some(path);
+        }
+        let cost_so_far = best_cost[&point];
+        if estimate > cost_so_far + heuristic(point, goal) {
+            // A stale entry; a cheaper route to this point has already been found.
+            continue;
+        }
+        expanded += 1;
+        if expanded > max_nodes {
+            break;
+        }
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let neighbor = (point.0 + dx, point.1 + dy);
+                if let Some(cost) = get_cost(neighbor.0, neighbor.1) {
+                    let step_cost = if dx != 0 && dy != 0 {
+                        cost * DIAGONAL_COST / ORTHOGONAL_COST
+                    } else {
+                        cost
+                    };
+                    let new_cost = cost_so_far + step_cost;
+                    if best_cost.get(&neighbor).is_none_or(|&old_cost| new_cost < old_cost) {
+                        best_cost.insert(neighbor, new_cost);
+                        came_from.insert(neighbor, point);
+                        open.push(OpenNode {
+                            estimate: new_cost + heuristic(neighbor, goal),
+                            point: neighbor,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    trace!("Exiting astar::find_path().");
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// A 10x10 open field, with walls wherever `walls` says.
+    fn get_cost(walls: &[(i32, i32)], x: i32, y: i32) -> Option<i32> {
+        if x < 0 || y < 0 || x >= 10 || y >= 10 || walls.contains(&(x, y)) {
+            None
+        } else {
+            Some(ORTHOGONAL_COST)
+        }
+    }
+
+    /// A straight path.
+    #[test]
+    fn straight() {
+        let path = find_path((1, 1), (5, 1), 1000, |x, y| get_cost(&[], x, y)).unwrap();
+        assert_eq!(vec![(2, 1), (3, 1), (4, 1), (5, 1)], path);
+    }
+
+    /// Diagonal movement is used.
+    #[test]
+    fn diagonal() {
+        let path = find_path((1, 1), (4, 4), 1000, |x, y| get_cost(&[], x, y)).unwrap();
+        assert_eq!(vec![(2, 2), (3, 3), (4, 4)], path);
+    }
+
+    /// Paths go around walls.
+    #[test]
+    fn around_wall() {
+        let walls = [(3, 0), (3, 1), (3, 2), (3, 3)];
+        let path = find_path((1, 1), (5, 1), 1000, |x, y| get_cost(&walls, x, y)).unwrap();
+        assert_eq!(Some(&(5, 1)), path.last());
+        assert!(path.contains(&(3, 4)));
+        assert!(path.iter().all(|point| !walls.contains(point)));
+    }
+
+    /// Expensive tiles are avoided when a cheaper route exists.
+    #[test]
+    fn weighted() {
+        let path = find_path((1, 1), (5, 1), 1000, |x, y| {
+            if (x, y) == (3, 1) {
+                Some(ORTHOGONAL_COST * 10)
+            } else {
+                get_cost(&[], x, y)
+            }
+        }).unwrap();
+        assert!(!path.contains(&(3, 1)));
+    }
+
+    /// Unreachable goals produce no path.
+    #[test]
+    fn unreachable() {
+        let walls = [(4, 3), (5, 3), (6, 3), (4, 4), (6, 4), (4, 5), (5, 5), (6, 5)];
+        assert_eq!(None, find_path((1, 1), (5, 4), 1000, |x, y| get_cost(&walls, x, y)));
+    }
+
+}
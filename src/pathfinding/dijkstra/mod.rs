@@ -0,0 +1,319 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use super::{ORTHOGONAL_COST, DIAGONAL_COST};
+
+/// The value of a location that can't be reached from any goal.
+pub const UNREACHABLE: i32 = i32::MAX;
+
+/// The different Dijkstra maps shared by agents each turn.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Hash, Serialize)]
+pub enum Kind {
+    /// Distance to the player.
+    DistanceToPlayer,
+    /// Distance to the nearest food.
+    DistanceToFood,
+    /// Distance to the nearest water fit to drink.
+    DistanceToWater,
+    /// Lower values are farther from the player; used for fleeing.
+    Safety,
+    /// Approach food while keeping away from the player.
+    Forage,
+}
+
+/// Each kind of map, in the order that the debug overlay cycles through them.
+pub const KINDS: [Kind; 5] = [
+    Kind::DistanceToPlayer,
+    Kind::DistanceToFood,
+    Kind::DistanceToWater,
+    Kind::Safety,
+    Kind::Forage,
+];
+
+/// A point on the open list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct OpenNode {
+    /// The value of the point when it was queued.
+    value: i32,
+    /// The point.
+    point: (i32, i32),
+}
+
+/// Reversed, so that the binary heap pops the lowest value first.
+impl Ord for OpenNode {
+    fn cmp(&self, other: &OpenNode) -> Ordering {
+        other.value.cmp(&self.value)
+            .then_with(|| self.point.cmp(&other.point))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &OpenNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The cost of reaching the nearest goal from every location on a map.
+///
+/// Agents move "downhill" to approach goals; inverted maps send them away.
+#[derive(Clone, Debug)]
+pub struct DijkstraMap {
+    /// The width of the map.
+    pub width: usize,
+    /// The height of the map.
+    pub height: usize,
+    /// The value at each location.
+    values: Vec<Vec<i32>>,
+}
+
+/// The cost of reaching the nearest goal from every location on a map.
+impl DijkstraMap {
+
+    /// Constructor; every location starts out unreachable.
+    pub fn new(width: usize, height: usize) -> Self {
+        DijkstraMap {
+            width,
+            height,
+            values: vec![vec![UNREACHABLE; height]; width],
+        }
+    }
+
+    /// Compute a map from one or more goals, each with its own starting value.
+    ///
+    /// `get_cost` returns the cost of an orthogonal step into a location, or
+    /// `None` if it is impassable.
+    pub fn compute<F>(width: usize, height: usize, goals: &[(i32, i32, i32)], get_cost: F) -> Self
+        where F: Fn(i32, i32) -> Option<i32> {
+        trace!("Entering DijkstraMap::compute().");
+        let mut map = DijkstraMap::new(width, height);
+        for &(x, y, value) in goals {
+            if map.get(x, y) > value {
+                map.set(x, y, value);
+            }
+        }
+        map.relax(get_cost);
+        trace!("Exiting DijkstraMap::compute().");
+        map
+    }
+
+    /// Indicates whether a pair of coordinates are in bounds of this map.
+    pub fn is_in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// The value at the specified location.
+    pub fn get(&self, x: i32, y: i32) -> i32 {
+        if !self.is_in_bounds(x, y) {
+            return UNREACHABLE;
+        }
+        self.values[x as usize][y as usize]
+    }
+
+    /// Set the value at the specified location.
+    pub fn set(&mut self, x: i32, y: i32, value: i32) {
+        if self.is_in_bounds(x, y) {
+            self.values[x as usize][y as usize] = value;
+        }
+    }
+
+    /// Propagate values outward until every location is at most one step's
+    /// cost above its cheapest neighbor.
+    pub fn relax<F>(&mut self, get_cost: F) where F: Fn(i32, i32) -> Option<i32> {
+        trace!("Entering DijkstraMap::relax().");
+        let mut open = BinaryHeap::new();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let value = self.values[x][y];
+                if value != UNREACHABLE {
+                    open.push(OpenNode {
+                        value,
+                        point: (x as i32, y as i32),
+                    });
+                }
+            }
+        }
+        while let Some(OpenNode { value, point }) = open.pop() {
+            if value > self.get(point.0, point.1) {
+                continue;
+            }
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let (x, y) = (point.0 + dx, point.1 + dy);
+                    if !self.is_in_bounds(x, y) {
+                        continue;
+                    }
+                    if let Some(cost) = get_cost(x, y) {
+                        let step_cost = if dx != 0 && dy != 0 {
+                            cost * DIAGONAL_COST / ORTHOGONAL_COST
+                        } else {
+                            cost
+                        };
+                        let new_value = value + step_cost;
+                        if new_value < self.get(x, y) {
+                            self.set(x, y, new_value);
+                            open.push(OpenNode {
+                                value: new_value,
+                                point: (x, y),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        trace!("Exiting DijkstraMap::relax().");
+    }
+
+    /// Combine several maps of the same size, each scaled by a weight.
+    ///
+    /// A location is unreachable if it is unreachable in any of the maps.
+    pub fn combine(maps: &[(&DijkstraMap, f64)]) -> DijkstraMap {
+        trace!("Entering DijkstraMap::combine().");
+        let (width, height) = match maps.first() {
+            Some((map, _)) => (map.width, map.height),
+            None => return DijkstraMap::new(0, 0),
+        };
+        let mut result = DijkstraMap::new(width, height);
+        for x in 0..width as i32 {
+            for y in 0..height as i32 {
+                let mut total = 0.0;
+                let mut is_reachable = true;
+                for (map, weight) in maps {
+                    let value = map.get(x, y);
+                    if value == UNREACHABLE {
+                        is_reachable = false;
+                        break;
+                    }
+                    total += f64::from(value) * weight;
+                }
+                if is_reachable {
+                    result.set(x, y, total.round() as i32);
+                }
+            }
+        }
+        result
+    }
+
+    /// An inverted copy of this map, rescanned so that moving downhill leads
+    /// away from the goals without running into dead ends.
+    ///
+    /// A coefficient somewhat larger than 1.0 (e.g. 1.2) encourages fleeing
+    /// entities to double back past their pursuer rather than be cornered.
+    pub fn invert<F>(&self, coefficient: f64, get_cost: F) -> DijkstraMap
+        where F: Fn(i32, i32) -> Option<i32> {
+        trace!("Entering DijkstraMap::invert().");
+        let mut result = DijkstraMap::new(self.width, self.height);
+        for x in 0..self.width as i32 {
+            for y in 0..self.height as i32 {
+                let value = self.get(x, y);
+                if value != UNREACHABLE {
+                    result.set(x, y, (-f64::from(value) * coefficient).round() as i32);
+                }
+            }
+        }
+        result.relax(get_cost);
+        result
+    }
+
+    /// The offset of the lowest neighboring location that is lower than the
+    /// specified location and passes the supplied test.
+    pub fn get_downhill_offset<F>(&self, x: i32, y: i32, is_passable: F) -> Option<(i32, i32)>
+        where F: Fn(i32, i32) -> bool {
+        let mut best_value = self.get(x, y);
+        let mut result = None;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let value = self.get(x + dx, y + dy);
+                if value < best_value && is_passable(x + dx, y + dy) {
+                    best_value = value;
+                    result = Some((dx, dy));
+                }
+            }
+        }
+        result
+    }
+
+    /// The lowest and highest reachable values in the map.
+    pub fn get_range(&self) -> Option<(i32, i32)> {
+        let mut result: Option<(i32, i32)> = None;
+        for column in &self.values {
+            for &value in column {
+                if value == UNREACHABLE {
+                    continue;
+                }
+                result = match result {
+                    Some((low, high)) => Some((low.min(value), high.max(value))),
+                    None => Some((value, value)),
+                };
+            }
+        }
+        result
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// A 10x10 open field, with walls wherever `walls` says.
+    fn get_cost(walls: &[(i32, i32)], x: i32, y: i32) -> Option<i32> {
+        if walls.contains(&(x, y)) {
+            None
+        } else {
+            Some(ORTHOGONAL_COST)
+        }
+    }
+
+    /// Values increase with distance from the goal.
+    #[test]
+    fn compute() {
+        let map = DijkstraMap::compute(10, 10, &[(0, 0, 0)], |x, y| get_cost(&[], x, y));
+        assert_eq!(0, map.get(0, 0));
+        assert_eq!(ORTHOGONAL_COST, map.get(1, 0));
+        assert_eq!(DIAGONAL_COST, map.get(1, 1));
+        assert_eq!(DIAGONAL_COST * 2 + ORTHOGONAL_COST, map.get(3, 2));
+    }
+
+    /// The nearest of several goals wins.
+    #[test]
+    fn multiple_goals() {
+        let map = DijkstraMap::compute(10, 10, &[(0, 0, 0), (9, 0, 0)], |x, y| get_cost(&[], x, y));
+        assert_eq!(ORTHOGONAL_COST, map.get(8, 0));
+        assert_eq!(ORTHOGONAL_COST, map.get(1, 0));
+    }
+
+    /// Walled-off locations are unreachable.
+    #[test]
+    fn unreachable() {
+        let walls = [(4, 3), (5, 3), (6, 3), (4, 4), (6, 4), (4, 5), (5, 5), (6, 5)];
+        let map = DijkstraMap::compute(10, 10, &[(0, 0, 0)], |x, y| get_cost(&walls, x, y));
+        assert_eq!(UNREACHABLE, map.get(5, 4));
+        assert_eq!(UNREACHABLE, map.get(4, 4));
+    }
+
+    /// Moving downhill approaches the goal; on an inverted map, it flees.
+    #[test]
+    fn downhill() {
+        let map = DijkstraMap::compute(10, 10, &[(0, 0, 0)], |x, y| get_cost(&[], x, y));
+        assert_eq!(Some((-1, -1)), map.get_downhill_offset(5, 5, |_, _| true));
+        assert_eq!(None, map.get_downhill_offset(0, 0, |_, _| true));
+        let inverted = map.invert(1.2, |x, y| get_cost(&[], x, y));
+        assert_eq!(Some((1, 1)), inverted.get_downhill_offset(5, 5, |_, _| true));
+    }
+
+    /// Combined maps are weighted sums.
+    #[test]
+    fn combine() {
+        let a = DijkstraMap::compute(10, 10, &[(0, 0, 0)], |x, y| get_cost(&[], x, y));
+        let b = DijkstraMap::compute(10, 10, &[(9, 0, 0)], |x, y| get_cost(&[], x, y));
+        let combined = DijkstraMap::combine(&[(&a, 1.0), (&b, 0.5)]);
+        assert_eq!(a.get(3, 0) + b.get(3, 0) / 2, combined.get(3, 0));
+    }
+
+}
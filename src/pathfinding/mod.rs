@@ -0,0 +1,94 @@
+use crate::command;
+use command::CompassDirection;
+use crate::component;
+use component::position::Position;
+use crate::game;
+use game::Game;
+
+/// The cost of an orthogonal step onto an ordinary tile.
+pub const ORTHOGONAL_COST: i32 = 10;
+
+/// The cost of a diagonal step onto an ordinary tile.
+pub const DIAGONAL_COST: i32 = 14;
+
+/// The extra cost of stepping onto a tile occupied by a blocking entity.
+pub const OCCUPIED_COST: i32 = 50;
+
+/// The maximum number of points to expand in a single search.
+pub const MAX_NODES: usize = 2000;
+
+/// A* search.
+pub mod astar;
+
+/// Dijkstra maps.
+pub mod dijkstra;
+
+/// The cost for an entity of an orthogonal step into the specified location.
+///
+/// Other blocking entities are soft obstacles: they make a location more
+/// expensive, rather than impassable, since they'll probably move.
+pub fn get_movement_cost(game: &Game, id: usize, x: i32, y: i32) -> Option<i32> {
+    if x < 0 || y < 0 || !game.map.is_in_bounds(x as usize, y as usize) {
+        return None;
+    }
+    let tile = game.map.get_tile(x as usize, y as usize);
+    if tile.blocks_movement {
+        return None;
+    }
+    let mut cost = tile.movement_cost * ORTHOGONAL_COST;
+    if game.get_entities(x, y)
+        .iter()
+        .any(|entity| entity.blocks_movement && entity.id != id) {
+        cost += OCCUPIED_COST;
+    }
+    Some(cost)
+}
+
+/// The cost of an orthogonal step into the specified location, ignoring entities.
+///
+/// This is suitable for maps that are shared by many entities.
+pub fn get_terrain_cost(game: &Game, x: i32, y: i32) -> Option<i32> {
+    if x < 0 || y < 0 || !game.map.is_in_bounds(x as usize, y as usize) {
+        return None;
+    }
+    let tile = game.map.get_tile(x as usize, y as usize);
+    if tile.blocks_movement {
+        return None;
+    }
+    Some(tile.movement_cost * ORTHOGONAL_COST)
+}
+
+/// Indicates whether an entity could step into the specified location right now.
+pub fn is_passable(game: &Game, id: usize, x: i32, y: i32) -> bool {
+    get_terrain_cost(game, x, y).is_some()
+        && !game.get_entities(x, y)
+            .iter()
+            .any(|entity| entity.blocks_movement && entity.id != id)
+}
+
+/// Find a path for an entity to the specified position.
+pub fn find_path(game: &Game, id: usize, goal: &Position) -> Option<Vec<Position>> {
+    trace!("Entering pathfinding::find_path().");
+    let start = game.entities[id].position?;
+    let goal_point = (goal.x, goal.y);
+    let path = astar::find_path((start.x, start.y), goal_point, MAX_NODES, |x, y| {
+        if (x, y) == goal_point {
+            // The goal is often occupied by the very thing we're approaching.
+            get_movement_cost(game, id, x, y).map(|_| ORTHOGONAL_COST)
+        } else {
+            get_movement_cost(game, id, x, y)
+        }
+    })?;
+    Some(path
+        .iter()
+        .map(|&(x, y)| Position::new(start.w, x, y, start.z))
+        .collect())
+}
+
+/// The direction of the first step along the best path to the specified position.
+pub fn get_next_step(game: &Game, id: usize, goal: &Position) -> Option<CompassDirection> {
+    trace!("Entering pathfinding::get_next_step().");
+    let start = game.entities[id].position?;
+    let path = find_path(game, id, goal)?;
+    start.direction_to(path.first()?)
+}
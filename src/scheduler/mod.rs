@@ -1,5 +1,12 @@
+use bear_lib_terminal::Color;
 use crate::command;
 use command::Command;
+use crate::component;
+use component::morale::{self, FEAR_LOSS, RECOVERY};
+use component::needs::{Kind as NeedKind, Level as NeedLevel};
+use component::status::Kind as StatusKind;
+use crate::effect;
+use effect::Effect;
 use crate::entity;
 use entity::Entity;
 use crate::game;
@@ -23,14 +30,34 @@ impl Scheduler {
     pub fn feed(&self, entities: &mut Vec<Entity>) {
         trace!("Entering Scheduler::feed().");
         for entity in entities {
+            let speed = entity.get_speed();
             if let Some(actor) = entity.actor.as_mut() {
-                debug!("Feeding entity {} ({}, {}) {} time ({} -> {}).", entity.name, entity.position.unwrap().x, entity.position.unwrap().y, actor.speed, actor.time, actor.time + actor.speed);
-                actor.time += actor.speed;
+                debug!("Feeding entity {} ({}, {}) {} time ({} -> {}).", entity.name, entity.position.unwrap().x, entity.position.unwrap().y, speed, actor.time, actor.time + speed);
+                actor.time += speed;
             }
         }
         trace!("Exiting Scheduler::feed().");
     }
 
+    /// Begin a new turn, performing any per-turn upkeep.
+    pub fn begin_turn(&self, game: &mut Game) {
+        trace!("Entering Scheduler::begin_turn().");
+        game.turns += 1;
+        burn_light_sources(game);
+        bleed(game);
+        tick_statuses(game);
+        tick_standings(game);
+        tick_needs(game);
+        decay_corpses(game);
+        regenerate(game);
+        game.update_light_map();
+        let threats = get_threats(game);
+        update_morale(&threats, game);
+        game.update_dijkstra_maps();
+        game.update_flee_maps(&threats);
+        trace!("Exiting Scheduler::begin_turn().");
+    }
+
     /// Gets the ID of the next actor who should act.
     pub fn next(&self, entities: &Vec<Entity>) -> Option<usize> {
         trace!("Entering Scheduler::next().");
@@ -55,8 +82,11 @@ impl Scheduler {
         let mut command_option = None;
         let entity = &mut game.entities[id];
         if let Some(actor) = entity.actor {
-            if let Some(agent) = entity.agent {
-                command_option = agent.algorithm.get_command(actor.time, id, game);
+            if let Some(mut agent) = entity.agent.take() {
+                let mut rng = game.rng.clone();
+                command_option = agent.get_command(actor.time, id, game, &mut rng);
+                game.rng = rng;
+                game.entities[id].agent = Some(agent);
             }
         }
         if let Some(command) = command_option {
@@ -70,3 +100,174 @@ impl Scheduler {
     }
 
 }
+
+/// Burn a turn's worth of fuel; only lights in the world burn, as a torch in a pack has no position.
+fn burn_light_sources(game: &mut Game) {
+    let mut extinguished_ids = vec![];
+    for (id, entity) in game.entities.iter_mut().enumerate().filter(|(_, entity)| entity.position.is_some()) {
+        if let Some(light_source) = entity.light_source.as_mut() {
+            if !light_source.burn() {
+                extinguished_ids.push(id);
+            }
+        }
+    }
+    for id in extinguished_ids {
+        Effect::ExtinguishLightSource.execute(id, game);
+    }
+}
+
+/// Wounds bleed.
+fn bleed(game: &mut Game) {
+    let mut bleeding = vec![];
+    for (id, entity) in game.entities.iter_mut().enumerate() {
+        if let Some(body) = entity.body.as_mut() {
+            let damage = body.bleed();
+            if damage > 0 {
+                bleeding.push((id, damage));
+            }
+        }
+    }
+    for (id, damage) in bleeding {
+        Effect::DamageEntityBody(id, damage).execute(id, game);
+    }
+}
+
+/// Statuses do their harm and wear off.
+fn tick_statuses(game: &mut Game) {
+    let mut afflicted = vec![];
+    for (id, entity) in game.entities.iter_mut().enumerate() {
+        if let Some(statuses) = entity.statuses.as_mut() {
+            let (damage, expired) = statuses.tick();
+            afflicted.push((id, damage, expired));
+        }
+    }
+    for (id, damage, expired) in afflicted {
+        let player_id = game.player_id;
+        if game.can_see_entity(player_id, id) {
+            for kind in expired {
+                let message = format!("{} is no longer {}.", game.entities[id].name, kind.get_adjective());
+                game.add_message(message, Color::from_rgb(160, 160, 160));
+            }
+        }
+        if damage > 0 {
+            Effect::DamageEntityBody(id, damage).execute(id, game);
+        }
+    }
+}
+
+/// Faction penalties run their course.
+fn tick_standings(game: &mut Game) {
+    let mut pardoned = vec![];
+    for (id, entity) in game.entities.iter_mut().enumerate() {
+        if let Some(standings) = entity.faction_standings.as_mut() {
+            for standing in standings.iter_mut() {
+                if standing.tick() {
+                    pardoned.push((id, standing.faction));
+                }
+            }
+        }
+    }
+    for (id, faction) in pardoned {
+        if id == game.player_id {
+            let message = format!("{} have lifted your punishment.", faction.get_name());
+            game.add_message(message, Color::from_rgb(160, 160, 160));
+        }
+    }
+}
+
+/// Needs build up, starving and exhausted creatures suffer for it.
+fn tick_needs(game: &mut Game) {
+    let turns = game.turns;
+    let mut needy = vec![];
+    for (id, entity) in game.entities.iter_mut().enumerate() {
+        let is_asleep = entity.has_status(StatusKind::Asleep);
+        if let Some(needs) = entity.needs.as_mut() {
+            let risen = needs.tick(is_asleep);
+            let damage = needs.get_starvation_damage(turns);
+            let is_collapsing = !is_asleep && needs.get_level(NeedKind::Fatigue) == NeedLevel::Critical;
+            needy.push((id, risen, damage, is_collapsing));
+        }
+    }
+    for (id, risen, damage, is_collapsing) in needy {
+        if id == game.player_id {
+            for (kind, level) in risen {
+                if let Some(adjective) = kind.get_adjective(level) {
+                    game.add_message(format!("You are {}.", adjective), Color::from_rgb(224, 160, 64));
+                }
+            }
+        }
+        if damage > 0 {
+            Effect::DamageEntityBody(id, damage).execute(id, game);
+        }
+        if is_collapsing {
+            let duration = game.entities[id].needs.as_ref().map_or(1, |needs| needs.get_sleep_duration());
+            Effect::InflictStatus(id, StatusKind::Asleep, duration).execute(id, game);
+        }
+    }
+}
+
+/// Corpses other than the player's rot.
+fn decay_corpses(game: &mut Game) {
+    let player_id = game.player_id;
+    let corpse_ids = game.entities
+        .iter()
+        .filter(|entity| entity.corpse.is_some() && entity.id != player_id)
+        .map(|entity| entity.id)
+        .collect::<Vec<usize>>();
+    for id in corpse_ids {
+        Effect::DecayCorpse.execute(id, game);
+    }
+}
+
+/// Bodies mend, unless they are starving.
+fn regenerate(game: &mut Game) {
+    let turns = game.turns;
+    let mut regenerating = vec![];
+    for (id, entity) in game.entities.iter_mut().enumerate() {
+        if entity.needs.as_ref().is_some_and(|needs| needs.is_starving()) {
+            continue;
+        }
+        if let Some(body) = entity.body.as_mut() {
+            let hit_points = body.regenerate(turns);
+            if hit_points > 0 {
+                regenerating.push((id, hit_points));
+            }
+        }
+    }
+    for (id, hit_points) in regenerating {
+        Effect::HealEntityBody(id, hit_points).execute(id, game);
+    }
+}
+
+/// The threats in sight of each entity with morale, looked up once for the turn.
+fn get_threats(game: &Game) -> Vec<(usize, Vec<usize>)> {
+    game.entities
+        .iter()
+        .filter(|entity| entity.morale.is_some())
+        .map(|entity| (entity.id, game.get_visible_threats(entity.id)))
+        .collect()
+}
+
+/// Morale recovers out of danger, and falls in sight of fearsome threats.
+fn update_morale(threats: &[(usize, Vec<usize>)], game: &mut Game) {
+    let mut morale_changes = vec![];
+    for (id, threat_ids) in threats {
+        let hit_points = game.entities[*id].body.as_ref().map_or(0, |body| body.total_hit_points);
+        let change = if threat_ids.is_empty() {
+            RECOVERY
+        } else {
+            let fearsome_count = threat_ids
+                .iter()
+                .filter_map(|&threat_id| game.entities[threat_id].body.as_ref())
+                .filter(|body| morale::is_fearsome(hit_points, body.total_hit_points))
+                .count() as i32;
+            -FEAR_LOSS * fearsome_count
+        };
+        if change != 0 {
+            morale_changes.push((*id, change));
+        }
+    }
+    for (id, change) in morale_changes {
+        Effect::ChangeMorale(change).execute(id, game);
+    }
+}
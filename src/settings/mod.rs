@@ -35,7 +35,7 @@ impl Settings {
     }
 
     /// Save the settings.
-    pub fn save(&self) -> Result<(), Box<Error>> {
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
         let data = serde_json::to_string(self)?;
         let mut file = File::create(SETTINGS_FILENAME.to_string())?;
         file.write_all(data.as_bytes())?;
@@ -45,7 +45,7 @@ impl Settings {
 }
 
 /// Load the settings.
-pub fn load() -> Result<Settings, Box<Error>> {
+pub fn load() -> Result<Settings, Box<dyn Error>> {
     let mut data = String::new();
     let mut file = File::open(SETTINGS_FILENAME.to_string())?;
     file.read_to_string(&mut data)?;
@@ -1,17 +1,31 @@
+use rand::Rng;
 use crate::agent;
 use agent::{Agent, Algorithm as AgentAlgorithm};
+use agent::utility::Profile as UtilityProfile;
 use crate::body;
-use body::Body;
+use body::Factory as BodyFactory;
 use crate::component;
 use component::actor::Actor;
+use component::experience::Experience;
 use component::light_source::Factory as LightSourceFactory;
+use component::morale::Morale;
+use component::needs::{Kind as NeedKind, Needs};
+use component::perception::Perception;
 use component::position::Position;
+use component::stats::Stats;
+use crate::combat;
+use combat::dice::Dice;
 use component::renderable::Factory as RenderableFactory;
 use crate::entity;
 use entity::Entity;
+use crate::faction;
+use faction::{Faction, Standing as FactionStanding};
+
+/// The needs of an animal: to eat, drink and sleep.
+const ANIMAL_NEEDS: [NeedKind; 3] = [NeedKind::Hunger, NeedKind::Thirst, NeedKind::Fatigue];
 
 /// The species that we support.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Species {
     /// Human.
     Human,
@@ -33,6 +47,53 @@ pub enum Species {
     MossSeed,
 }
 
+/// The species that we support.
+impl Species {
+
+    /// The default allegiances of a member of this species.
+    pub fn get_default_standings(self) -> Vec<FactionStanding> {
+        use Species::*;
+        let standings: &[(Species, i32)] = match self {
+            Human => &[(Human, 100), (Orc, -100), (Troll, -100), (Goblin, -100), (Kobold, -100), (Chicken, -100), (Mushroom, -100), (Moss, -100)],
+            Orc => &[(Orc, 100), (Troll, 50), (Human, -100), (Goblin, -100), (Kobold, -100)],
+            Troll => &[(Troll, 100), (Orc, 50), (Human, -100), (Goblin, -100), (Kobold, -100)],
+            Goblin => &[(Goblin, 100), (Human, -100), (Orc, -100), (Troll, -100), (Kobold, -100), (Chicken, -100), (Mushroom, -100)],
+            Kobold => &[(Kobold, 100), (Human, -100), (Orc, -100), (Troll, -100), (Goblin, -100), (Mushroom, -100)],
+            Chicken => &[(Chicken, 100), (Moss, -100), (Goblin, -100), (Kobold, -100)],
+            Mushroom => &[(Mushroom, 100), (Goblin, -100), (Kobold, -100)],
+            Moss => &[(Moss, 100), (Goblin, -100), (Kobold, -100)],
+            MossSeed => &[(Moss, 100)],
+        };
+        standings
+            .iter()
+            .map(|&(species, standing)| FactionStanding::new(Faction::Species(species), standing))
+            .collect()
+    }
+
+    /// The experience awarded for killing a level-one member of this species.
+    pub fn get_experience_value(self) -> i32 {
+        use Species::*;
+        match self {
+            Human => 10,
+            Orc => 10,
+            Troll => 25,
+            Goblin => 5,
+            Kobold => 6,
+            Chicken => 1,
+            Mushroom => 1,
+            Moss => 0,
+            MossSeed => 0,
+        }
+    }
+
+    /// Indicates whether members of this species band together into packs.
+    pub fn forms_packs(self) -> bool {
+        use Species::*;
+        matches!(self, Orc | Goblin | Kobold)
+    }
+
+}
+
 /// Factory.
 #[derive(Clone, Copy, Debug)]
 pub enum Factory {
@@ -59,60 +120,60 @@ pub enum Factory {
 /// Factory.
 impl Factory {
 
-    /// Create an entity of the specified type.
-    pub fn create(&self) -> Entity {
+    /// Create an entity of the specified type, drawing anything random about it from the generator.
+    pub fn create<R: Rng>(&self, rng: &mut R) -> Entity {
         use Factory::*;
         match self {
             Orc => {
                 let mut orc = Entity::new("Orc".to_string());
-                self.apply(&mut orc);
+                self.apply(&mut orc, rng);
                 orc
             },
             Troll => {
                 let mut troll = Entity::new("Troll".to_string());
-                self.apply(&mut troll);
+                self.apply(&mut troll, rng);
                 troll
             },
             Goblin => {
                 let mut goblin = Entity::new("Goblin".to_string());
-                self.apply(&mut goblin);
+                self.apply(&mut goblin, rng);
                 goblin
             },
             Kobold => {
                 let mut kobold = Entity::new("Kobold".to_string());
-                self.apply(&mut kobold);
+                self.apply(&mut kobold, rng);
                 kobold
             },
             Chicken => {
                 let mut chicken = Entity::new("Chicken".to_string());
-                self.apply(&mut chicken);
+                self.apply(&mut chicken, rng);
                 chicken
             },
             Mushroom => {
                 let mut mushroom = Entity::new("Mushroom".to_string());
-                self.apply(&mut mushroom);
+                self.apply(&mut mushroom, rng);
                 mushroom
             },
             Moss => {
                 let mut moss = Entity::new("Moss".to_string());
-                self.apply(&mut moss);
+                self.apply(&mut moss, rng);
                 moss
             },
             MossSeed => {
                 let mut moss_seed = Entity::new("MossSeed".to_string());
-                self.apply(&mut moss_seed);
+                self.apply(&mut moss_seed, rng);
                 moss_seed
             },
             Human => {
                 let mut human = Entity::new("Human".to_string());
-                self.apply(&mut human);
+                self.apply(&mut human, rng);
                 human
             },
         }
     }
 
-    /// Create an entity of the specified type.
-    pub fn apply(&self, entity: &mut Entity) {
+    /// Make an entity into the specified type, drawing anything random about it from the generator.
+    pub fn apply<R: Rng>(&self, entity: &mut Entity, rng: &mut R) {
         use Factory::*;
         match self {
             Orc => {
@@ -120,125 +181,132 @@ impl Factory {
                     time: 0,
                     speed: 11,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 15,
-                    current_hit_points: 15,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::ApproachAndFightPlayer,
-                });
+                entity.body = Some(BodyFactory::Humanoid.create(15).with_regeneration(Some(12)));
+                entity.agent = Some(Agent::new(AgentAlgorithm::ApproachAndFightPlayer));
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Orc.create());
+                entity.stats = Some(Stats::new(3, 2, 1, Dice::new(1, 8, 0)));
+                entity.experience = Some(Experience::new(1));
+                entity.morale = Some(Morale::new(100));
+                entity.needs = Some(Needs::new(&ANIMAL_NEEDS));
+                entity.perception = Some(Perception::new(32, 0));
                 entity.blocks_movement = true;
                 entity.species = Some(Species::Orc);
+                entity.faction_standings = Some(Species::Orc.get_default_standings());
             },
             Troll => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 9,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 25,
-                    current_hit_points: 25,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::ApproachAndFightPlayer,
-                });
-                entity.light_source = Some(LightSourceFactory::Random.create());
+                entity.body = Some(BodyFactory::Humanoid.create(25).with_regeneration(Some(3)));
+                entity.agent = Some(Agent::new(AgentAlgorithm::ApproachAndFightPlayer));
+                entity.light_source = Some(LightSourceFactory::create_random(rng));
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Troll.create());
+                entity.stats = Some(Stats::new(4, 1, 3, Dice::new(2, 6, 0)));
+                entity.experience = Some(Experience::new(1));
+                entity.morale = Some(Morale::new(150));
+                entity.needs = Some(Needs::new(&ANIMAL_NEEDS));
+                entity.perception = Some(Perception::new(16, 0));
                 entity.blocks_movement = true;
                 entity.species = Some(Species::Troll);
+                entity.faction_standings = Some(Species::Troll.get_default_standings());
             },
             Goblin => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 12,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 5,
-                    current_hit_points: 5,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::ApproachAndFightPlayer,
-                });
+                entity.body = Some(BodyFactory::Humanoid.create(5).with_regeneration(Some(15)));
+                entity.agent = Some(Agent::new(AgentAlgorithm::Utility(UtilityProfile::Skulker)));
                 entity.light_source = None;
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Goblin.create());
+                entity.stats = Some(Stats::new(2, 3, 0, Dice::new(1, 6, 0)).with_ranged_attack(8, Dice::new(1, 6, 0)));
+                entity.experience = Some(Experience::new(1));
+                entity.morale = Some(Morale::new(60));
+                entity.needs = Some(Needs::new(&ANIMAL_NEEDS));
+                entity.perception = Some(Perception::new(48, 16));
                 entity.blocks_movement = true;
                 entity.species = Some(Species::Goblin);
+                entity.faction_standings = Some(Species::Goblin.get_default_standings());
             },
             Kobold => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 14,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 7,
-                    current_hit_points: 7,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::ApproachAndFightPlayer,
-                });
+                entity.body = Some(BodyFactory::Humanoid.create(7).with_regeneration(Some(15)));
+                entity.agent = Some(Agent::new(AgentAlgorithm::ApproachAndShootPlayer));
                 entity.light_source = None;
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Kobold.create());
+                entity.stats = Some(Stats::new(1, 3, 0, Dice::new(1, 4, 0)).with_ranged_attack(6, Dice::new(1, 4, 0)));
+                entity.experience = Some(Experience::new(1));
+                entity.morale = Some(Morale::new(50));
+                entity.needs = Some(Needs::new(&ANIMAL_NEEDS));
+                entity.perception = Some(Perception::new(48, 16));
                 entity.blocks_movement = true;
                 entity.species = Some(Species::Kobold);
+                entity.faction_standings = Some(Species::Kobold.get_default_standings());
             },
             Chicken => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 14,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 2,
-                    current_hit_points: 2,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::BeChicken,
-                });
+                entity.body = Some(BodyFactory::Avian.create(2).with_regeneration(Some(20)));
+                entity.agent = Some(Agent::new(AgentAlgorithm::BeChicken));
                 entity.light_source = None;
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Chicken.create());
+                entity.stats = Some(Stats::new(0, 4, 0, Dice::new(1, 2, 0)));
+                entity.experience = Some(Experience::new(1));
+                entity.morale = Some(Morale::new(10));
+                entity.needs = Some(Needs::new(&ANIMAL_NEEDS));
+                entity.perception = Some(Perception::new(0, 0));
                 entity.blocks_movement = true;
                 entity.species = Some(Species::Chicken);
+                entity.faction_standings = Some(Species::Chicken.get_default_standings());
             },
             Mushroom => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 12,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 2,
-                    current_hit_points: 2,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::BeMushroom,
-                });;
+                entity.body = Some(BodyFactory::Mass.create(2).with_regeneration(None));
+                entity.agent = Some(Agent::new(AgentAlgorithm::BeMushroom));
                 entity.light_source = None;
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Mushroom.create());
+                entity.stats = None;
+                entity.experience = None;
+                entity.morale = None;
+                entity.needs = None;
+                entity.perception = None;
                 entity.blocks_movement = false;
                 entity.species = Some(Species::Mushroom);
+                entity.faction_standings = Some(Species::Mushroom.get_default_standings());
             },
             Moss => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 12,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 2,
-                    current_hit_points: 2,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::BeMoss,
-                });
-                entity.light_source = Some(LightSourceFactory::Moss.create());
+                entity.body = Some(BodyFactory::Mass.create(2).with_regeneration(None));
+                entity.agent = Some(Agent::new(AgentAlgorithm::BeMoss));
+                entity.light_source = Some(LightSourceFactory::Moss.create_with_rng(rng));
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Moss.create());
+                entity.stats = None;
+                entity.experience = None;
+                entity.morale = None;
+                entity.needs = None;
+                entity.perception = None;
                 entity.blocks_movement = false;
                 entity.species = Some(Species::Moss);
+                entity.faction_standings = Some(Species::Moss.get_default_standings());
             },
             MossSeed => {
                 entity.actor = Some(Actor {
@@ -246,32 +314,37 @@ impl Factory {
                     speed: 12,
                 });
                 entity.body = None;
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::BeMossSeed,
-                });
+                entity.agent = Some(Agent::new(AgentAlgorithm::BeMossSeed));
                 entity.light_source = None;
                 entity.position = Some(Position::default());
                 entity.renderable = None;
+                entity.morale = None;
+                entity.needs = None;
+                entity.perception = None;
+                entity.stats = None;
+                entity.experience = None;
                 entity.blocks_movement = false;
                 entity.species = Some(Species::MossSeed);
+                entity.faction_standings = Some(Species::MossSeed.get_default_standings());
             },
             Human => {
                 entity.actor = Some(Actor {
                     time: 0,
                     speed: 12,
                 });
-                entity.body = Some(Body {
-                    total_hit_points: 10,
-                    current_hit_points: 10,
-                });
-                entity.agent = Some(Agent {
-                    algorithm: AgentAlgorithm::ApproachPlayer,
-                });
-                entity.light_source = Some(LightSourceFactory::Torch.create());
+                entity.body = Some(BodyFactory::Humanoid.create(10).with_regeneration(Some(10)));
+                entity.agent = Some(Agent::new(AgentAlgorithm::ApproachPlayer));
+                entity.light_source = Some(LightSourceFactory::Torch.create_with_rng(rng));
                 entity.position = Some(Position::default());
                 entity.renderable = Some(RenderableFactory::Human.create());
+                entity.stats = Some(Stats::new(2, 2, 0, Dice::new(1, 6, 0)));
+                entity.experience = Some(Experience::new(1));
+                entity.morale = Some(Morale::new(80));
+                entity.needs = Some(Needs::new(&ANIMAL_NEEDS));
+                entity.perception = Some(Perception::new(0, 0));
                 entity.blocks_movement = true;
                 entity.species = Some(Species::Human);
+                entity.faction_standings = Some(Species::Human.get_default_standings());
             },
         }
     }
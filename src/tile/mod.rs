@@ -10,6 +10,10 @@ pub struct Tile {
     pub blocks_movement: bool,
     /// Whether this object is opaque.
     pub blocks_light: bool,
+    /// How costly this tile is to move through, relative to an ordinary floor.
+    pub movement_cost: i32,
+    /// Whether a creature standing here can drink from this tile.
+    pub is_drinkable: bool,
 }
 
 impl Tile {
@@ -20,6 +24,8 @@ impl Tile {
             renderable: RenderableFactory::Floor.create(),
             blocks_movement: false,
             blocks_light: false,
+            movement_cost: 1,
+            is_drinkable: false,
         }
     }
 
@@ -29,6 +35,19 @@ impl Tile {
             renderable: RenderableFactory::Wall.create(),
             blocks_movement: true,
             blocks_light: true,
+            movement_cost: 1,
+            is_drinkable: false,
+        }
+    }
+
+    /// Create a puddle of water, shallow enough to wade through.
+    pub fn puddle() -> Self {
+        Tile {
+            renderable: RenderableFactory::Puddle.create(),
+            blocks_movement: false,
+            blocks_light: false,
+            movement_cost: 2,
+            is_drinkable: true,
         }
     }
 
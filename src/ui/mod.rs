@@ -1,15 +1,31 @@
+use std::cmp;
 use std::fmt;
 use std::collections::HashSet;
+use std::time::Instant;
 use bear_lib_terminal::terminal as blt;
 use bear_lib_terminal::Color;
 use blt::config::font as blt_font;
 use blt::Event;
-use bear_lib_terminal::geometry::Size;
+use bear_lib_terminal::geometry::{Point, Size};
+use crate::combat;
+use combat::projectile::THROW_RANGE;
 use crate::command;
 use command::Command;
+use command::CommandPreconditionResult;
 use command::CompassDirection;
+use crate::component;
+use component::experience::Growth;
+use component::needs::Level as NeedLevel;
+use component::position::Position;
+use crate::effect;
+use effect::Effect;
 use crate::game;
 use game::Game;
+use crate::math;
+use math::line;
+use crate::pathfinding;
+use pathfinding::ORTHOGONAL_COST;
+use pathfinding::dijkstra::{Kind as DijkstraMapKind, KINDS as DIJKSTRA_MAP_KINDS, UNREACHABLE};
 use crate::settings;
 use settings::Settings;
 
@@ -18,12 +34,128 @@ use settings::Settings;
 pub enum Domain {
     /// Hack 'n' Slash
     Explore,
+    /// Choosing a position with a cursor.
+    Target(TargetingMode, Position),
+    /// Choosing how to grow on gaining a level.
+    LevelUp,
+    /// Browsing the items carried.
+    Inventory(InventoryMode),
+    /// Moving items between the specified container and the inventory.
+    Transfer(usize, TransferSide),
+}
+
+/// The list from which items are chosen when transferring.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransferSide {
+    /// Take items from the container.
+    Container,
+    /// Put items into the container.
+    Inventory,
+}
+
+/// The list from which items are chosen when transferring.
+impl TransferSide {
+
+    /// The other side.
+    pub fn get_opposite(self) -> TransferSide {
+        use TransferSide::*;
+        match self {
+            Container => Inventory,
+            Inventory => Container,
+        }
+    }
+
+}
+
+/// The reasons for browsing the items carried.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InventoryMode {
+    /// Examine an item.
+    View,
+    /// Drop an item.
+    Drop,
+    /// Wield, wear or put away an item.
+    Equip,
+    /// Drink a potion.
+    Quaff,
+    /// Read a scroll.
+    Read,
+    /// Eat some food.
+    Eat,
+}
+
+/// The reasons for browsing the items carried.
+impl InventoryMode {
+
+    /// The prompt shown above the list of items.
+    pub fn get_prompt(self) -> &'static str {
+        use InventoryMode::*;
+        match self {
+            View => "You are carrying:",
+            Drop => "Drop which item?",
+            Equip => "Wield, wear or put away which item?",
+            Quaff => "Quaff which potion?",
+            Read => "Read which scroll?",
+            Eat => "Eat what?",
+        }
+    }
+
+}
+
+/// The keys used to choose items from a menu, in order.
+const MENU_KEYS: [blt::KeyCode; 26] = {
+    use blt::KeyCode::*;
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M,
+        N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ]
+};
+
+/// The index of the menu entry chosen by a key, if any.
+fn get_menu_index(key: blt::KeyCode) -> Option<usize> {
+    MENU_KEYS.iter().position(|&menu_key| menu_key == key)
+}
+
+/// The reasons for choosing a position with a cursor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TargetingMode {
+    /// Shoot at the position.
+    Fire,
+    /// Throw something at the position.
+    Throw,
+}
+
+/// The reasons for choosing a position with a cursor.
+impl TargetingMode {
+
+    /// The command to execute once the position has been chosen.
+    pub fn get_command(self, position: Position) -> Command {
+        use TargetingMode::*;
+        match self {
+            Fire => Command::RangedAttack(position),
+            Throw => Command::Throw(position),
+        }
+    }
+
+    /// The range available to the entity in this mode.
+    pub fn get_range(self, id: usize, game: &Game) -> i32 {
+        use TargetingMode::*;
+        match self {
+            Fire => game.entities[id].get_stats().map_or(0, |stats| stats.range),
+            Throw => THROW_RANGE,
+        }
+    }
+
 }
 
 /// The User Interface abstraction.
 pub struct Ui {
     /// The settings object.
     pub settings: Settings,
+    /// When the UI was created; used to animate between turns.
+    pub start_time: Instant,
+    /// The Dijkstra map drawn over the game map, for debugging.
+    pub overlay: Option<DijkstraMapKind>,
 }
 
 /// The User Interface abstraction.
@@ -33,9 +165,17 @@ impl Ui {
     pub fn new(settings: &Settings) -> Self {
         Ui {
             settings: settings.clone(),
+            start_time: Instant::now(),
+            overlay: None,
         }
     }
 
+    /// The time, in seconds, since the UI was created.
+    pub fn get_time(&self) -> f64 {
+        let elapsed = self.start_time.elapsed();
+        elapsed.as_secs() as f64 + f64::from(elapsed.subsec_millis()) / 1000.0
+    }
+
     /// Open the window.
     pub fn open(&self) {
         blt::open("Azymus", self.settings.display.width as u32, self.settings.display.height as u32);
@@ -74,15 +214,32 @@ impl Ui {
         let player = &game.entities[player_id];
         if let Some(fov) = &player.field_of_view {
             map.draw(&self, fov, game);
+            if let Some(kind) = self.overlay {
+                self.draw_overlay(kind, game);
+            }
+            self.draw_messages(game);
+            self.draw_status(player_id, game);
+            self.draw_equipment(player_id, game);
+            match game.input_domain {
+                Domain::Target(mode, position) => self.draw_targeting(mode, position, player_id, game),
+                Domain::LevelUp => self.draw_level_up(),
+                Domain::Inventory(mode) => self.draw_inventory(mode, player_id, game),
+                Domain::Transfer(container_id, side) => self.draw_transfer(container_id, side, player_id, game),
+                Domain::Explore => {},
+            }
             let position = blt::state::mouse::position();
-            let fov_map = fov.map.lock().unwrap();
+            let is_in_fov = fov.map.lock().unwrap().is_in_fov(position.x, position.y);
             if map.is_in_bounds(position.x as usize, position.y as usize) {
-                if fov_map.is_in_fov(position.x, position.y) {
+                if is_in_fov {
                     blt::with_colors(Color::from_rgb(255, 255, 255), Color::from_rgb(0, 0, 0), || {
+                        let xy_items = map.get_items(position.x as usize, position.y as usize)
+                            .unwrap_or_default();
                         let xy_entities = map.get_entities(position.x as usize, position.y as usize)
                             .unwrap_or(HashSet::new());
-                        let entities = xy_entities
+                        let entities = xy_items
                             .iter()
+                            .chain(xy_entities.iter())
+                            .filter(|&&id| game.can_see_entity(player_id, id))
                             .map(|&id| game.entities[id].clone());
                         if let Some(top_entity) = entities.last() {
                             blt::print_xy(position.x + 1, position.y, &format!("{} (#{}) ({}, {})", &top_entity.name, &top_entity.id, position.x, position.y));
@@ -94,8 +251,248 @@ impl Ui {
         self.refresh();
     }
 
+    /// Cycle through the Dijkstra map overlays, then turn the overlay off.
+    pub fn cycle_overlay(&mut self) {
+        let index = match self.overlay {
+            Some(kind) => DIJKSTRA_MAP_KINDS.iter().position(|&other| other == kind).map_or(0, |index| index + 1),
+            None => 0,
+        };
+        self.overlay = DIJKSTRA_MAP_KINDS.get(index).copied();
+    }
+
+    /// Draw a Dijkstra map over the game map, for debugging.
+    pub fn draw_overlay(&self, kind: DijkstraMapKind, game: &Game) {
+        if let Some(dijkstra_map) = game.dijkstra_maps.get(&kind) {
+            if let Some((low, high)) = dijkstra_map.get_range() {
+                let range = cmp::max(high - low, 1);
+                for y in 0..dijkstra_map.height as i32 {
+                    for x in 0..dijkstra_map.width as i32 {
+                        let value = dijkstra_map.get(x, y);
+                        if value == UNREACHABLE {
+                            continue;
+                        }
+                        let fraction = f64::from(value - low) / f64::from(range);
+                        let fg_color = Color::from_rgb((255.0 * fraction) as u8, (255.0 * (1.0 - fraction)) as u8, 0);
+                        let bg_color = blt::pick_background_color(Point::new(x, y));
+                        let digit = std::char::from_digit(((value / ORTHOGONAL_COST).abs() % 10) as u32, 10).unwrap_or('?');
+                        blt::with_colors(fg_color, bg_color, || blt::put_xy(x, y, digit));
+                    }
+                }
+            }
+            blt::with_colors(Color::from_rgb(255, 255, 255), Color::from_rgb(0, 0, 0), || {
+                blt::print_xy(0, 0, &format!("{:?}", kind));
+            });
+        }
+    }
+
+    /// Draw the most recent messages in the panel beneath the map.
+    pub fn draw_messages(&self, game: &Game) {
+        let top = self.settings.map.height;
+        let rows = cmp::max(self.settings.display.height - top, 0) as usize;
+        for (index, message) in game.messages.get_recent(rows).iter().enumerate() {
+            blt::with_colors(message.color, Color::from_rgb(0, 0, 0), || {
+                blt::print_xy(1, top + index as i32, &message.text);
+            });
+        }
+    }
+
+    /// Draw the player's status to the right of the message log.
+    pub fn draw_status(&self, player_id: usize, game: &Game) {
+        let player = &game.entities[player_id];
+        if let Some(body) = &player.body {
+            let level = player.experience.map_or(1, |experience| experience.level);
+            let mut text = format!("Lv {} HP: {}/{}", level, body.current_hit_points, body.total_hit_points);
+            if let Some(statuses) = &player.statuses {
+                for status in &statuses.entries {
+                    text.push_str(&format!(" {}({})", status.kind.get_adjective(), status.duration));
+                }
+            }
+            if let Some(needs) = &player.needs {
+                for need in &needs.entries {
+                    if let Some(adjective) = need.kind.get_adjective(NeedLevel::from_value(need.current)) {
+                        text.push_str(&format!(" {}", adjective));
+                    }
+                }
+            }
+            let x = self.settings.display.width - text.len() as i32 - 1;
+            let color = if body.bleeding > 0 {
+                Color::from_rgb(255, 96, 96)
+            } else {
+                Color::from_rgb(255, 255, 255)
+            };
+            blt::with_colors(color, Color::from_rgb(0, 0, 0), || {
+                blt::print_xy(x, self.settings.map.height, &text);
+            });
+        }
+    }
+
+    /// Draw the contents of a container beside the items carried.
+    pub fn draw_transfer(&self, container_id: usize, side: TransferSide, player_id: usize, game: &Game) {
+        let columns = [
+            (TransferSide::Container, container_id, 2),
+            (TransferSide::Inventory, player_id, 42),
+        ];
+        for &(column_side, id, x) in columns.iter() {
+            let title = if column_side == TransferSide::Container {
+                game.entities[container_id].name.clone()
+            } else {
+                "You are carrying:".to_string()
+            };
+            let mut lines = vec![title];
+            let items = game.entities[id].inventory.as_ref().map_or(vec![], |inventory| inventory.items.clone());
+            if items.is_empty() {
+                lines.push("(nothing)".to_string());
+            }
+            for (index, &item_id) in items.iter().enumerate() {
+                lines.push(format!("[{:?}] {}", MENU_KEYS[index], game.get_item_name(item_id)));
+            }
+            let color = if column_side == side {
+                Color::from_rgb(255, 255, 255)
+            } else {
+                Color::from_rgb(128, 128, 128)
+            };
+            blt::with_colors(color, Color::from_rgb(0, 0, 0), || {
+                for (index, line) in lines.iter().enumerate() {
+                    blt::print_xy(x, 2 + index as i32, line);
+                }
+            });
+        }
+        blt::with_colors(Color::from_rgb(255, 255, 255), Color::from_rgb(0, 0, 0), || {
+            blt::print_xy(2, 1, "Tab to switch sides, a letter to move an item, Escape to close");
+        });
+    }
+
+    /// Draw the player's equipment beneath the status line.
+    pub fn draw_equipment(&self, player_id: usize, game: &Game) {
+        if let Some(equipment) = &game.entities[player_id].equipment {
+            let text = equipment.entries
+                .iter()
+                .map(|entry| format!("{}: {}", entry.equippable.slot.get_name(), game.entities[entry.item_id].name))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let x = self.settings.display.width - text.len() as i32 - 1;
+            blt::with_colors(Color::from_rgb(192, 192, 192), Color::from_rgb(0, 0, 0), || {
+                blt::print_xy(x, self.settings.map.height + 1, &text);
+            });
+        }
+    }
+
+    /// Draw the line of fire from the player to the targeting cursor.
+    pub fn draw_targeting(&self, mode: TargetingMode, position: Position, player_id: usize, game: &Game) {
+        if let Some(origin) = game.entities[player_id].position {
+            let range = mode.get_range(player_id, game);
+            let in_range = (origin.x - position.x).abs() <= range && (origin.y - position.y).abs() <= range;
+            let color = if in_range {
+                Color::from_rgb(255, 255, 0)
+            } else {
+                Color::from_rgb(255, 64, 64)
+            };
+            let line = line::get_line(origin.x, origin.y, position.x, position.y);
+            for &(x, y) in line.iter().skip(1) {
+                let bg_color = blt::pick_background_color(Point::new(x, y));
+                let glyph = if (x, y) == (position.x, position.y) { 'X' } else { '*' };
+                blt::with_colors(color, bg_color, || blt::put_xy(x, y, glyph));
+            }
+            blt::with_colors(Color::from_rgb(255, 255, 255), Color::from_rgb(0, 0, 0), || {
+                blt::print_xy(0, 0, &format!("{:?}: arrows to aim, Enter to confirm, Escape to cancel", mode));
+            });
+        }
+    }
+
+    /// Draw the prompt for choosing how to grow on gaining a level.
+    pub fn draw_level_up(&self) {
+        let lines = [
+            "You feel stronger!  Choose how to grow:".to_string(),
+            format!("[A] {}", Growth::Attack.describe()),
+            format!("[D] {}", Growth::Defense.describe()),
+            format!("[V] {}", Growth::Vitality.describe()),
+        ];
+        blt::with_colors(Color::from_rgb(255, 224, 64), Color::from_rgb(0, 0, 0), || {
+            for (index, line) in lines.iter().enumerate() {
+                blt::print_xy(2, 2 + index as i32, line);
+            }
+        });
+    }
+
+    /// Draw the list of items carried, each labelled with the key that chooses it.
+    pub fn draw_inventory(&self, mode: InventoryMode, player_id: usize, game: &Game) {
+        if let Some(inventory) = &game.entities[player_id].inventory {
+            let mut lines = vec![mode.get_prompt().to_string()];
+            if inventory.items.is_empty() {
+                lines.push("(nothing)".to_string());
+            }
+            let equipment = &game.entities[player_id].equipment;
+            for (index, &item_id) in inventory.items.iter().enumerate() {
+                let item = &game.entities[item_id];
+                let weight = item.item.map_or(0, |item| item.weight);
+                let mut line = format!("[{:?}] {} ({})", MENU_KEYS[index], game.get_item_name(item_id), weight);
+                if let (Some(equipment), Some(equippable)) = (equipment, item.equippable) {
+                    if equipment.contains(item_id) {
+                        line.push_str(&format!(" [{}]", equippable.slot.get_name()));
+                    }
+                }
+                lines.push(line);
+            }
+            lines.push(format!("Weight: {}/{}  Escape to close", game.get_carried_weight(player_id), inventory.max_weight));
+            blt::with_colors(Color::from_rgb(255, 255, 255), Color::from_rgb(0, 0, 0), || {
+                for (index, line) in lines.iter().enumerate() {
+                    blt::print_xy(2, 2 + index as i32, line);
+                }
+            });
+        }
+    }
+
+    /// Start choosing a position, aiming at the nearest visible hostile in range.
+    pub fn begin_targeting(&self, mode: TargetingMode, player_id: usize, game: &mut Game) {
+        let range = mode.get_range(player_id, game);
+        if range <= 0 {
+            game.add_message("You have nothing to shoot with.".to_string(), Color::from_rgb(160, 160, 160));
+            return;
+        }
+        let player = &game.entities[player_id];
+        if let Some(origin) = player.position {
+            let target = game.entities
+                .iter()
+                .filter(|entity| entity.id != player_id && entity.would_attack(player) && game.can_see_entity(player_id, entity.id))
+                .filter_map(|entity| entity.position)
+                .filter(|position| (origin.x - position.x).abs() <= range && (origin.y - position.y).abs() <= range)
+                .min_by_key(|position| cmp::max((origin.x - position.x).abs(), (origin.y - position.y).abs()))
+                .unwrap_or(origin);
+            game.input_domain = Domain::Target(mode, target);
+        }
+    }
+
+    /// Show the scene of the player's death until a key is pressed.
+    pub fn show_game_over(&mut self, player_id: usize, game: &Game) {
+        self.render(player_id, game);
+        let text = format!("You died on turn {}.  Press any key to exit.", game.turns);
+        let x = cmp::max((self.settings.map.width - text.len() as i32) / 2, 0);
+        let y = self.settings.map.height / 2;
+        blt::with_colors(Color::from_rgb(255, 64, 64), Color::from_rgb(0, 0, 0), || {
+            blt::print_xy(x, y, &text);
+        });
+        self.refresh();
+        loop {
+            match blt::wait_event() {
+                Some(Event::KeyPressed { .. }) | Some(Event::Close) | None => break,
+                _ => {},
+            }
+        }
+    }
+
     /// Handle input.
     pub fn handle_input(&mut self, player_id: usize, game: &mut Game) {
+        let frame_delay = 1000 / cmp::max(self.settings.display.fps_limit, 1);
+        let unspent = game.entities[player_id].experience.map_or(0, |experience| experience.unspent);
+        if let Domain::Explore = game.input_domain {
+            if unspent > 0 {
+                game.input_domain = Domain::LevelUp;
+            }
+        }
+        while !blt::has_input() {
+            self.render(player_id, game);
+            blt::delay(frame_delay);
+        }
         let event = blt::wait_event();
         use Event::*;
         match event {
@@ -121,9 +518,132 @@ impl Ui {
                             Left => Command::Walk(CompassDirection::West).execute(player_id, game),
                             Right => Command::Walk(CompassDirection::East).execute(player_id, game),
                             Period => Command::Wait.execute(player_id, game),
-                            _ => {
-                                println!("{:?}", key);
+                            S => Command::ToggleSneak.execute(player_id, game),
+                            E => {
+                                match Command::Eat.check_preconditions(player_id, game) {
+                                    CommandPreconditionResult::Denied(_) => game.input_domain = Inventory(InventoryMode::Eat),
+                                    _ => Command::Eat.execute(player_id, game),
+                                }
+                            },
+                            O => {
+                                if let Some(container_id) = game.get_container_near(player_id) {
+                                    let command = Command::Open(container_id);
+                                    let is_denied = matches!(command.check_preconditions(player_id, game), CommandPreconditionResult::Denied(_));
+                                    command.execute(player_id, game);
+                                    if !is_denied {
+                                        game.input_domain = Transfer(container_id, TransferSide::Container);
+                                    }
+                                } else {
+                                    game.add_message("There is nothing here to open.".to_string(), Color::from_rgb(160, 160, 160));
+                                }
                             },
+                            Q => {
+                                match Command::Drink.check_preconditions(player_id, game) {
+                                    CommandPreconditionResult::Denied(_) => game.input_domain = Inventory(InventoryMode::Quaff),
+                                    _ => Command::Drink.execute(player_id, game),
+                                }
+                            },
+                            R => game.input_domain = Inventory(InventoryMode::Read),
+                            G => Command::PickUp.execute(player_id, game),
+                            I => {
+                                Command::ListInventory.execute(player_id, game);
+                                game.input_domain = Inventory(InventoryMode::View);
+                            },
+                            P => game.input_domain = Inventory(InventoryMode::Drop),
+                            W => game.input_domain = Inventory(InventoryMode::Equip),
+                            D => self.cycle_overlay(),
+                            F => self.begin_targeting(TargetingMode::Fire, player_id, game),
+                            T => self.begin_targeting(TargetingMode::Throw, player_id, game),
+                            _ => {},
+                        }
+                    },
+                    LevelUp => {
+                        use blt::KeyCode;
+                        use KeyCode::*;
+                        let growth = match key {
+                            A => Some(Growth::Attack),
+                            D => Some(Growth::Defense),
+                            V => Some(Growth::Vitality),
+                            _ => None,
+                        };
+                        if let Some(growth) = growth {
+                            Effect::Grow(growth).execute(player_id, game);
+                            game.add_message(format!("You gain {}.", growth.describe()), Color::from_rgb(255, 224, 64));
+                            game.input_domain = Explore;
+                        }
+                    },
+                    Inventory(mode) => {
+                        use blt::KeyCode::Escape;
+                        if key == Escape {
+                            game.input_domain = Explore;
+                            return;
+                        }
+                        let item_id = get_menu_index(key)
+                            .and_then(|index| game.entities[player_id].inventory.as_ref()?.items.get(index).copied());
+                        if let Some(item_id) = item_id {
+                            game.input_domain = Explore;
+                            match mode {
+                                InventoryMode::View => {
+                                    let weight = game.entities[item_id].item.map_or(0, |item| item.weight);
+                                    let message = format!("{}, weighing {}.", game.get_item_name(item_id), weight);
+                                    game.add_message(message, Color::from_rgb(192, 192, 192));
+                                },
+                                InventoryMode::Drop => Command::Drop(item_id).execute(player_id, game),
+                                InventoryMode::Quaff => Command::Quaff(item_id).execute(player_id, game),
+                                InventoryMode::Read => Command::Read(item_id).execute(player_id, game),
+                                InventoryMode::Eat => Command::EatItem(item_id).execute(player_id, game),
+                                InventoryMode::Equip => {
+                                    let is_equipped = game.entities[player_id].equipment
+                                        .as_ref()
+                                        .is_some_and(|equipment| equipment.contains(item_id));
+                                    if is_equipped {
+                                        Command::Unequip(item_id).execute(player_id, game);
+                                    } else {
+                                        Command::Equip(item_id).execute(player_id, game);
+                                    }
+                                },
+                            }
+                        }
+                    },
+                    Transfer(container_id, side) => {
+                        use blt::KeyCode::{Escape, Tab};
+                        if key == Escape {
+                            game.input_domain = Explore;
+                            return;
+                        }
+                        if key == Tab {
+                            game.input_domain = Transfer(container_id, side.get_opposite());
+                            return;
+                        }
+                        let owner_id = match side {
+                            TransferSide::Container => container_id,
+                            TransferSide::Inventory => player_id,
+                        };
+                        let item_id = get_menu_index(key)
+                            .and_then(|index| game.entities[owner_id].inventory.as_ref()?.items.get(index).copied());
+                        if let Some(item_id) = item_id {
+                            match side {
+                                TransferSide::Container => Command::TakeFrom(container_id, item_id).execute(player_id, game),
+                                TransferSide::Inventory => Command::PutInto(container_id, item_id).execute(player_id, game),
+                            }
+                        }
+                    },
+                    Target(mode, position) => {
+                        use blt::KeyCode;
+                        use KeyCode::*;
+                        match key {
+                            Escape => {
+                                game.input_domain = Explore;
+                            },
+                            Up => game.input_domain = Target(mode, position.to_north()),
+                            Down => game.input_domain = Target(mode, position.to_south()),
+                            Left => game.input_domain = Target(mode, position.to_west()),
+                            Right => game.input_domain = Target(mode, position.to_east()),
+                            Enter | F | T | MouseLeft => {
+                                game.input_domain = Explore;
+                                mode.get_command(position).execute(player_id, game);
+                            },
+                            _ => {},
                         }
                     },
                 }
@@ -137,16 +657,23 @@ impl Ui {
                         let position = blt::state::mouse::position();
                         blt::print(position, &format!("{}", delta));
                     },
+                    Target(_, _) | LevelUp | Inventory(_) | Transfer(_, _) => {},
                 }
             },
             Some(MouseMove {
-                x: _,
-                y: _,
+                x,
+                y,
             }) => {
                 use Domain::*;
                 match game.input_domain {
                     Explore => {
-                    }
+                    },
+                    LevelUp | Inventory(_) | Transfer(_, _) => {},
+                    Target(mode, position) => {
+                        if game.map.is_in_bounds(x as usize, y as usize) {
+                            game.input_domain = Target(mode, Position::new(position.w, x, y, position.z));
+                        }
+                    },
                 }
             },
             _ => {},